@@ -0,0 +1,362 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype,
+    Address, Env, String,
+};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              STORAGE KEYS
+// ═══════════════════════════════════════════════════════════════════════════════
+// Token metadata and the admin live in INSTANCE storage (shared TTL).
+// Balances live in PERSISTENT storage (one entry per holder).
+// Allowances live in TEMPORARY storage (they expire on their own anyway).
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceDataKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    Decimals,
+    Name,
+    Symbol,
+    TotalSupply,
+    Balance(Address),
+    Allowance(AllowanceDataKey),
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              TTL CONSTANTS
+// ═══════════════════════════════════════════════════════════════════════════════
+// TTL is measured in LEDGERS (1 ledger ≈ 5-6 seconds)
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+// TTL for instance data (admin, metadata, total supply)
+const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;         // Extend to 7 days
+const INSTANCE_LIFETIME_THRESHOLD: u32 = DAY_IN_LEDGERS;      // Extend when < 1 day left
+
+// TTL for balances (each holder's entry is extended separately)
+const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;         // Extend to 30 days
+const BALANCE_LIFETIME_THRESHOLD: u32 = 29 * DAY_IN_LEDGERS;  // Extend when < 29 days left
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              ALLOWANCE VALUE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,   // Allowance is void after this ledger
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              SEP-41 EVENTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Approve {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              MAIN CONTRACT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct TokenContract;
+
+#[contractimpl]
+impl TokenContract {
+
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     CONSTRUCTOR
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Runs once at deploy time: stores the admin and the token metadata
+    pub fn __constructor(env: Env, admin: Address, decimal: u32, name: String, symbol: String) {
+        assert!(decimal <= 18, "Decimal must not be greater than 18");
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Decimals, &decimal);
+        env.storage().instance().set(&DataKey::Name, &name);
+        env.storage().instance().set(&DataKey::Symbol, &symbol);
+        env.storage().instance().set(&DataKey::TotalSupply, &0i128);
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     METADATA (VIEW)
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn name(env: Env) -> String {
+        env.storage().instance().get(&DataKey::Name).unwrap()
+    }
+
+    pub fn symbol(env: Env) -> String {
+        env.storage().instance().get(&DataKey::Symbol).unwrap()
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Decimals).unwrap()
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0)
+    }
+
+    pub fn admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     BALANCES & ALLOWANCES (VIEW)
+    // ─────────────────────────────────────────────────────────────────────────
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        Self::read_balance(&env, &id)
+    }
+
+    /// Returns 0 once the allowance has passed its expiration ledger
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        Self::read_allowance(&env, &from, &spender).amount
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     USER FUNCTIONS
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Let `spender` move up to `amount` of `from`'s tokens until `expiration_ledger`
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        Self::check_nonnegative_amount(amount);
+        Self::extend_instance_ttl(&env);
+
+        Self::write_allowance(&env, &from, &spender, amount, expiration_ledger);
+
+        Approve { from, spender, amount, expiration_ledger }.publish(&env);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        Self::check_nonnegative_amount(amount);
+        Self::extend_instance_ttl(&env);
+
+        Self::spend_balance(&env, &from, amount);
+        Self::receive_balance(&env, &to, amount);
+
+        Transfer { from, to, amount }.publish(&env);
+    }
+
+    /// Move tokens on behalf of `from`, consuming `spender`'s allowance
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        Self::check_nonnegative_amount(amount);
+        Self::extend_instance_ttl(&env);
+
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::spend_balance(&env, &from, amount);
+        Self::receive_balance(&env, &to, amount);
+
+        Transfer { from, to, amount }.publish(&env);
+    }
+
+    pub fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        Self::check_nonnegative_amount(amount);
+        Self::extend_instance_ttl(&env);
+
+        Self::spend_balance(&env, &from, amount);
+        Self::decrease_supply(&env, amount);
+
+        Burn { from, amount }.publish(&env);
+    }
+
+    /// Burn tokens on behalf of `from`, consuming `spender`'s allowance
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        Self::check_nonnegative_amount(amount);
+        Self::extend_instance_ttl(&env);
+
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::spend_balance(&env, &from, amount);
+        Self::decrease_supply(&env, amount);
+
+        Burn { from, amount }.publish(&env);
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     ADMIN FUNCTIONS
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Create new tokens (only the admin set in the constructor can call this)
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        Self::check_nonnegative_amount(amount);
+        let admin = Self::admin(env.clone());
+        admin.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        Self::receive_balance(&env, &to, amount);
+        Self::increase_supply(&env, amount);
+
+        Mint { to, amount }.publish(&env);
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     INTERNAL HELPERS
+    // ─────────────────────────────────────────────────────────────────────────
+
+    fn check_nonnegative_amount(amount: i128) {
+        assert!(amount >= 0, "Amount must not be negative");
+    }
+
+    fn read_balance(env: &Env, id: &Address) -> i128 {
+        let key = DataKey::Balance(id.clone());
+        if let Some(balance) = env.storage().persistent().get::<DataKey, i128>(&key) {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+            balance
+        } else {
+            0
+        }
+    }
+
+    fn write_balance(env: &Env, id: &Address, amount: i128) {
+        let key = DataKey::Balance(id.clone());
+        env.storage().persistent().set(&key, &amount);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+
+    fn receive_balance(env: &Env, id: &Address, amount: i128) {
+        let balance = Self::read_balance(env, id);
+        let new_balance = balance.checked_add(amount).expect("Balance overflow");
+        Self::write_balance(env, id, new_balance);
+    }
+
+    fn spend_balance(env: &Env, id: &Address, amount: i128) {
+        let balance = Self::read_balance(env, id);
+        assert!(balance >= amount, "Insufficient balance");
+        Self::write_balance(env, id, balance - amount);
+    }
+
+    fn increase_supply(env: &Env, amount: i128) {
+        let supply = Self::total_supply(env.clone());
+        let new_supply = supply.checked_add(amount).expect("Total supply overflow");
+        env.storage().instance().set(&DataKey::TotalSupply, &new_supply);
+    }
+
+    fn decrease_supply(env: &Env, amount: i128) {
+        let supply = Self::total_supply(env.clone());
+        env.storage().instance().set(&DataKey::TotalSupply, &(supply - amount));
+    }
+
+    fn read_allowance(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+        let key = DataKey::Allowance(AllowanceDataKey {
+            from: from.clone(),
+            spender: spender.clone(),
+        });
+        match env.storage().temporary().get::<DataKey, AllowanceValue>(&key) {
+            Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+            Some(allowance) => AllowanceValue {
+                amount: 0,
+                expiration_ledger: allowance.expiration_ledger,
+            },
+            None => AllowanceValue {
+                amount: 0,
+                expiration_ledger: 0,
+            },
+        }
+    }
+
+    fn write_allowance(
+        env: &Env,
+        from: &Address,
+        spender: &Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) {
+        let allowance = AllowanceValue { amount, expiration_ledger };
+
+        // A non-zero allowance must expire in the future
+        assert!(
+            amount == 0 || expiration_ledger >= env.ledger().sequence(),
+            "Expiration ledger is in the past"
+        );
+
+        let key = DataKey::Allowance(AllowanceDataKey {
+            from: from.clone(),
+            spender: spender.clone(),
+        });
+        env.storage().temporary().set(&key, &allowance);
+
+        // Keep the entry alive exactly as long as the allowance is valid
+        if amount > 0 {
+            let live_for = expiration_ledger - env.ledger().sequence();
+            env.storage().temporary().extend_ttl(&key, live_for, live_for);
+        }
+    }
+
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+        let allowance = Self::read_allowance(env, from, spender);
+        assert!(allowance.amount >= amount, "Insufficient allowance");
+
+        if amount > 0 {
+            Self::write_allowance(
+                env,
+                from,
+                spender,
+                allowance.amount - amount,
+                allowance.expiration_ledger,
+            );
+        }
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+mod test;
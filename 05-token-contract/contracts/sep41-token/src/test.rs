@@ -0,0 +1,189 @@
+#![cfg(test)]
+
+use crate::{TokenContract, TokenContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    symbol_short, Address, Env, FromVal, IntoVal, String,
+};
+
+fn create_token<'a>(env: &Env, admin: &Address) -> TokenContractClient<'a> {
+    let contract_id = env.register(
+        TokenContract,
+        (
+            admin,
+            7u32,
+            String::from_str(env, "Stellar Token"),
+            String::from_str(env, "STK"),
+        ),
+    );
+    TokenContractClient::new(env, &contract_id)
+}
+
+#[test]
+fn test_metadata() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    assert_eq!(token.name(), String::from_str(&env, "Stellar Token"));
+    assert_eq!(token.symbol(), String::from_str(&env, "STK"));
+    assert_eq!(token.decimals(), 7);
+    assert_eq!(token.total_supply(), 0);
+    assert_eq!(token.admin(), admin);
+}
+
+#[test]
+fn test_mint_and_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&alice, &1000);
+
+    // Mint must be authorized by the admin, not the recipient
+    assert_eq!(env.auths()[0].0, admin);
+
+    assert_eq!(token.balance(&alice), 1000);
+    assert_eq!(token.total_supply(), 1000);
+
+    token.transfer(&alice, &bob, &300);
+
+    // Transfer emits a single SEP-41 event
+    let events = env.events().all();
+    assert_eq!(events.len(), 1);
+    let (_, topics, data) = events.get(0).unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("transfer"), alice.clone(), bob.clone()).into_val(&env)
+    );
+    assert_eq!(i128::from_val(&env, &data), 300);
+
+    assert_eq!(token.balance(&alice), 700);
+    assert_eq!(token.balance(&bob), 300);
+    assert_eq!(token.total_supply(), 1000);
+}
+
+#[test]
+fn test_approve_and_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&alice, &1000);
+    token.approve(&alice, &spender, &500, &200);
+    assert_eq!(token.allowance(&alice, &spender), 500);
+
+    token.transfer_from(&spender, &alice, &bob, &400);
+    assert_eq!(token.balance(&alice), 600);
+    assert_eq!(token.balance(&bob), 400);
+    assert_eq!(token.allowance(&alice, &spender), 100);
+}
+
+#[test]
+fn test_allowance_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.approve(&alice, &spender, &500, &100);
+    assert_eq!(token.allowance(&alice, &spender), 500);
+
+    // Move past the expiration ledger
+    env.ledger().with_mut(|li| li.sequence_number = 101);
+    assert_eq!(token.allowance(&alice, &spender), 0);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient allowance")]
+fn test_transfer_from_after_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&alice, &1000);
+    token.approve(&alice, &spender, &500, &100);
+
+    env.ledger().with_mut(|li| li.sequence_number = 101);
+    token.transfer_from(&spender, &alice, &bob, &100);
+}
+
+#[test]
+fn test_burn_and_burn_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&alice, &1000);
+    token.burn(&alice, &100);
+    assert_eq!(token.balance(&alice), 900);
+    assert_eq!(token.total_supply(), 900);
+
+    token.approve(&alice, &spender, &200, &200);
+    token.burn_from(&spender, &alice, &150);
+    assert_eq!(token.balance(&alice), 750);
+    assert_eq!(token.total_supply(), 750);
+    assert_eq!(token.allowance(&alice, &spender), 50);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance")]
+fn test_transfer_insufficient_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&alice, &100);
+    token.transfer(&alice, &bob, &101);
+}
+
+#[test]
+#[should_panic(expected = "Amount must not be negative")]
+fn test_negative_amount_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.transfer(&alice, &bob, &-1);
+}
+
+#[test]
+#[should_panic]
+fn test_mint_requires_admin_auth() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    // No auths mocked: the admin has not signed
+    token.mint(&alice, &1000);
+}
@@ -1,9 +1,95 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, symbol_short, Env, Symbol};
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Symbol, Vec,
+};
 
-// Storage Key - Using short symbol (9 chars max)
-// COUNT = 5 chars, so we use symbol_short!
-const COUNT: Symbol = symbol_short!("COUNT");
+// Every counter has a name. Shared counters are keyed by the name alone,
+// per-user counters by (user, name), so "views" for two users never clash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CounterId {
+    Global(Symbol),          // e.g. Global("views")
+    User(Address, Symbol),   // e.g. User(alice, "nonce")
+}
+
+// Roles live in INSTANCE storage (small, contract-wide config).
+// Counters live in PERSISTENT storage, one entry (and one TTL) each.
+// Quota usage lives in TEMPORARY storage: a window nobody reads any
+// more simply expires, so there is nothing to clean up.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Owner,              // Set once by the constructor
+    Operator(Address),  // Present while the owner has granted the role
+    Counter(CounterId), // Current value of one counter
+    Config(CounterId),  // CounterConfig, only stored once configure was called
+    RateLimit(CounterId),                 // RateLimit, turns on quota mode
+    Usage(CounterId, Address, u32),       // Increments by one address in one window (temporary)
+    HistoryLen(CounterId),                // Number of changes ever recorded for a counter
+    History(CounterId, u32),              // ChangeRecord in ring slot `index % HISTORY_CAPACITY`
+}
+
+// What happens when a change would leave [min, max]:
+//   Saturate: stop at the bound (the default, like decrement at 0 always did)
+//   Wrap:     continue from the other bound, e.g. max + 1 → min
+//   Reject:   fail the call with Overflow / Underflow
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    Saturate,
+    Wrap,
+    Reject,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterConfig {
+    pub min: u32,                 // Lowest allowed value (also what reset sets)
+    pub max: u32,                 // Highest allowed value
+    pub step: u32,                // Amount increment/decrement move by (>= 1)
+    pub policy: OverflowPolicy,   // Behaviour at the bounds
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CounterOp {
+    Increment,
+    Decrement,
+    Add,
+    Reset,
+    Configure,  // configure clamped the value into the new bounds
+}
+
+// One entry of a counter's change history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeRecord {
+    pub ledger: u32,        // env.ledger().sequence() of the change
+    pub timestamp: u64,     // env.ledger().timestamp() of the change
+    pub caller: Address,    // Who made the change
+    pub op: CounterOp,
+    pub new_value: u32,     // Counter value right after the change
+}
+
+// Published on every increment/decrement/add/reset, and when configure clamps.
+// Topics: ("counter_changed", counter), data: the ChangeRecord
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterChanged {
+    #[topic]
+    pub counter: CounterId,
+    pub record: ChangeRecord,
+}
+
+// Quota mode: any address may increment the counter, but at most
+// `max_per_window` times per window of `window_ledgers` ledgers.
+// Windows are fixed: window n covers ledgers [n * window_ledgers, (n + 1) * window_ledgers).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimit {
+    pub max_per_window: u32,
+    pub window_ledgers: u32,
+}
 
 // TTL Constants (in ledgers, ~5 seconds per ledger)
 // Stellar produces ~1 ledger every 5 seconds
@@ -15,68 +101,393 @@ const DAY_IN_LEDGERS: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;      // Extend by 7 days
 const INSTANCE_LIFETIME_THRESHOLD: u32 = DAY_IN_LEDGERS;   // Extend when < 1 day left
 
+// Each counter is extended on its own, so idle counters can expire
+// without dragging the busy ones (or the contract) along
+const COUNTER_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;          // Extend to 30 days
+const COUNTER_LIFETIME_THRESHOLD: u32 = 29 * DAY_IN_LEDGERS;   // Extend when < 29 days left
+
+// Only the latest changes of each counter are kept; older slots get overwritten
+const HISTORY_CAPACITY: u32 = 64;
+
+// Errors come back to the caller as Error(Contract, #code)
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CounterError {
+    Unauthorized = 1,   // caller is neither the owner nor an operator
+    Overflow = 2,       // result above max with OverflowPolicy::Reject
+    Underflow = 3,      // result below min with OverflowPolicy::Reject
+    InvalidConfig = 4,  // min > max, step == 0, or a zero / over-long rate limit
+    RateLimited = 5,    // caller used up their quota for the current window
+}
+
 #[contract]
 pub struct CounterContract;
 
 #[contractimpl]
 impl CounterContract {
     
-    /// Increment the counter by 1
-    pub fn increment(env: Env) -> u32 {
+    /// Runs once at deploy time: the owner can mutate the counter
+    /// and grant or revoke operators
+    pub fn __constructor(env: Env, owner: Address) {
+        env.storage().instance().set(&DataKey::Owner, &owner);
+        Self::extend_instance_ttl(&env);
+    }
+    
+    /// Increment the counter by one step (1 unless configured).
+    /// Rate-limited counters accept any signer, within that signer's quota.
+    pub fn increment(env: Env, caller: Address, counter: CounterId) -> Result<u32, CounterError> {
+        match Self::get_rate_limit(env.clone(), counter.clone()) {
+            Some(limit) => {
+                caller.require_auth();
+                Self::use_quota(&env, &counter, &caller, &limit)?;
+            }
+            None => Self::require_operator(&env, &caller)?,
+        }
+        
         // IMPORTANT: Extend TTL on every state-changing operation
         Self::extend_instance_ttl(&env);
         
-        let mut count: u32 = env.storage().instance().get(&COUNT).unwrap_or(0);
-        count += 1;
-        env.storage().instance().set(&COUNT, &count);
+        let config = Self::get_config(env.clone(), counter.clone());
+        let count = Self::read_count(&env, &counter);
+        let count = Self::apply(&config, count, config.step as i64)?;
+        Self::write_count(&env, &counter, count);
+        Self::record_change(&env, &counter, &caller, CounterOp::Increment, count);
         
-        return count;
+        Ok(count)
     }
 
-    /// Decrement the counter by 1
-    pub fn decrement(env: Env) -> u32 {
+    /// Decrement the counter by one step
+    pub fn decrement(env: Env, caller: Address, counter: CounterId) -> Result<u32, CounterError> {
+        Self::require_operator(&env, &caller)?;
         Self::extend_instance_ttl(&env);
         
-        let mut count: u32 = env.storage().instance().get(&COUNT).unwrap_or(0);
+        let config = Self::get_config(env.clone(), counter.clone());
+        let count = Self::read_count(&env, &counter);
         
-        if count > 0 {
-            count -= 1;
-        }
+        // By default this stays at 0 instead of underflowing
+        let count = Self::apply(&config, count, -(config.step as i64))?;
         
-        env.storage().instance().set(&COUNT, &count);
-        return count;
+        Self::write_count(&env, &counter, count);
+        Self::record_change(&env, &counter, &caller, CounterOp::Decrement, count);
+        Ok(count)
     }
 
-    /// Add a specific value to the counter
-    pub fn add(env: Env, value: u32) -> u32 {
+    /// Add a specific value to the counter (the step does not apply)
+    pub fn add(env: Env, caller: Address, counter: CounterId, value: u32) -> Result<u32, CounterError> {
+        Self::require_operator(&env, &caller)?;
         Self::extend_instance_ttl(&env);
         
-        let mut count: u32 = env.storage().instance().get(&COUNT).unwrap_or(0);
-        count += value;
-        env.storage().instance().set(&COUNT, &count);
+        let config = Self::get_config(env.clone(), counter.clone());
+        let count = Self::read_count(&env, &counter);
+        let count = Self::apply(&config, count, value as i64)?;
+        Self::write_count(&env, &counter, count);
+        Self::record_change(&env, &counter, &caller, CounterOp::Add, count);
         
-        return count;
+        Ok(count)
     }
 
-    /// Get the current count value (read-only, but still good to extend TTL)
-    pub fn get_count(env: Env) -> u32 {
+    /// Get the current count value (0 for a counter never written).
+    /// Read-only, but still good to extend TTL
+    pub fn get_count(env: Env, counter: CounterId) -> u32 {
         // Optional: extend TTL even on reads to keep data alive
         Self::extend_instance_ttl(&env);
         
-        return env.storage().instance().get(&COUNT).unwrap_or(0);
+        Self::read_count(&env, &counter)
     }
 
-    /// Reset the counter to 0
-    pub fn reset(env: Env) {
+    /// Reset the counter to its minimum (0 unless configured)
+    pub fn reset(env: Env, caller: Address, counter: CounterId) -> Result<(), CounterError> {
+        Self::require_operator(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        
+        let config = Self::get_config(env.clone(), counter.clone());
+        Self::write_count(&env, &counter, config.min);
+        Self::record_change(&env, &counter, &caller, CounterOp::Reset, config.min);
+        Ok(())
+    }
+    
+    // ============================================
+    // PER-COUNTER CONFIGURATION
+    // ============================================
+    
+    /// Set bounds, step and overflow policy for one counter (owner only).
+    /// The current value is clamped into the new [min, max]; if that moves
+    /// it, the change is recorded like any other.
+    pub fn configure(env: Env, counter: CounterId, config: CounterConfig) -> Result<(), CounterError> {
+        let owner = Self::get_owner(env.clone());
+        owner.require_auth();
+        Self::extend_instance_ttl(&env);
+        
+        if config.min > config.max || config.step == 0 {
+            return Err(CounterError::InvalidConfig);
+        }
+        
+        let key = DataKey::Config(counter.clone());
+        env.storage().persistent().set(&key, &config);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+        
+        let count = Self::read_count(&env, &counter);
+        let clamped = count.clamp(config.min, config.max);
+        if clamped != count {
+            Self::write_count(&env, &counter, clamped);
+            Self::record_change(&env, &counter, &owner, CounterOp::Configure, clamped);
+        }
+        Ok(())
+    }
+    
+    /// Configuration of a counter; unconfigured counters use
+    /// 0..=u32::MAX, step 1, Saturate
+    pub fn get_config(env: Env, counter: CounterId) -> CounterConfig {
+        let key = DataKey::Config(counter);
+        if let Some(config) = env.storage().persistent().get::<DataKey, CounterConfig>(&key) {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+            config
+        } else {
+            CounterConfig {
+                min: 0,
+                max: u32::MAX,
+                step: 1,
+                policy: OverflowPolicy::Saturate,
+            }
+        }
+    }
+    
+    // ============================================
+    // RATE-LIMITED COUNTERS (QUOTAS)
+    // ============================================
+    
+    /// Put a counter into quota mode (owner only).
+    /// A window may not outlast the longest TTL a usage entry can get.
+    pub fn set_rate_limit(env: Env, counter: CounterId, limit: RateLimit) -> Result<(), CounterError> {
+        Self::get_owner(env.clone()).require_auth();
+        Self::extend_instance_ttl(&env);
+        
+        if limit.max_per_window == 0
+            || limit.window_ledgers == 0
+            || limit.window_ledgers > env.storage().max_ttl()
+        {
+            return Err(CounterError::InvalidConfig);
+        }
+        
+        let key = DataKey::RateLimit(counter);
+        env.storage().persistent().set(&key, &limit);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+        Ok(())
+    }
+    
+    /// Back to normal mode: only the owner and operators may increment (owner only)
+    pub fn clear_rate_limit(env: Env, counter: CounterId) {
+        Self::get_owner(env.clone()).require_auth();
+        Self::extend_instance_ttl(&env);
+        
+        env.storage().persistent().remove(&DataKey::RateLimit(counter));
+    }
+    
+    /// Every quota-mode increment reads this, which also extends its TTL
+    pub fn get_rate_limit(env: Env, counter: CounterId) -> Option<RateLimit> {
+        let key = DataKey::RateLimit(counter);
+        let limit = env.storage().persistent().get(&key)?;
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+        Some(limit)
+    }
+    
+    /// Increments `account` has left in the current window,
+    /// or None if the counter is not rate limited
+    pub fn get_remaining_quota(env: Env, counter: CounterId, account: Address) -> Option<u32> {
+        let limit = Self::get_rate_limit(env.clone(), counter.clone())?;
+        let key = Self::usage_key(&env, &counter, &account, &limit);
+        let used: u32 = env.storage().temporary().get(&key).unwrap_or(0);
+        
+        Some(limit.max_per_window.saturating_sub(used))
+    }
+    
+    // ============================================
+    // CHANGE HISTORY
+    // ============================================
+    
+    /// Number of changes ever recorded for a counter. Only the last
+    /// HISTORY_CAPACITY of them can still be read.
+    pub fn get_history_len(env: Env, counter: CounterId) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::HistoryLen(counter))
+            .unwrap_or(0)
+    }
+    
+    /// Up to `limit` changes, oldest first, starting at change number `start`.
+    /// Indexes that were already overwritten are skipped.
+    pub fn get_history(env: Env, counter: CounterId, start: u32, limit: u32) -> Vec<ChangeRecord> {
+        let len = Self::get_history_len(env.clone(), counter.clone());
+        let start = start.max(len.saturating_sub(HISTORY_CAPACITY));
+        let end = start.saturating_add(limit.min(HISTORY_CAPACITY)).min(len);
+        
+        let mut records = Vec::new(&env);
+        for index in start..end {
+            let key = DataKey::History(counter.clone(), index % HISTORY_CAPACITY);
+            if let Some(record) = env.storage().persistent().get(&key) {
+                records.push_back(record);
+            }
+        }
+        records
+    }
+    
+    /// Keep one counter alive without touching its value, together with
+    /// its config and rate limit (anyone can call this)
+    pub fn extend_counter_ttl(env: Env, counter: CounterId) {
+        for key in [
+            DataKey::Counter(counter.clone()),
+            DataKey::Config(counter.clone()),
+            DataKey::RateLimit(counter),
+        ] {
+            if env.storage().persistent().has(&key) {
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+            }
+        }
+    }
+    
+    // ============================================
+    // ROLES
+    // ============================================
+    
+    pub fn get_owner(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Owner).unwrap()
+    }
+    
+    /// Let `operator` mutate the counter (owner only)
+    pub fn grant_operator(env: Env, operator: Address) {
+        Self::get_owner(env.clone()).require_auth();
         Self::extend_instance_ttl(&env);
         
-        env.storage().instance().set(&COUNT, &0u32);
+        env.storage().instance().set(&DataKey::Operator(operator), &true);
+    }
+    
+    /// Take the operator role away again (owner only)
+    pub fn revoke_operator(env: Env, operator: Address) {
+        Self::get_owner(env.clone()).require_auth();
+        Self::extend_instance_ttl(&env);
+        
+        env.storage().instance().remove(&DataKey::Operator(operator));
+    }
+    
+    pub fn is_operator(env: Env, account: Address) -> bool {
+        env.storage().instance().has(&DataKey::Operator(account))
     }
     
     // ============================================
-    // INTERNAL HELPER FUNCTION FOR TTL MANAGEMENT
+    // INTERNAL HELPER FUNCTIONS
     // ============================================
     
+    /// The caller must sign, and must be the owner or an operator
+    fn require_operator(env: &Env, caller: &Address) -> Result<(), CounterError> {
+        caller.require_auth();
+        
+        if *caller == Self::get_owner(env.clone()) || Self::is_operator(env.clone(), caller.clone()) {
+            Ok(())
+        } else {
+            Err(CounterError::Unauthorized)
+        }
+    }
+    
+    /// Usage entry for the window the current ledger falls into
+    fn usage_key(env: &Env, counter: &CounterId, account: &Address, limit: &RateLimit) -> DataKey {
+        let window = env.ledger().sequence() / limit.window_ledgers;
+        DataKey::Usage(counter.clone(), account.clone(), window)
+    }
+    
+    /// Count one increment against `account`'s quota, or fail if it is used up
+    fn use_quota(env: &Env, counter: &CounterId, account: &Address, limit: &RateLimit) -> Result<(), CounterError> {
+        let key = Self::usage_key(env, counter, account, limit);
+        let used: u32 = env.storage().temporary().get(&key).unwrap_or(0);
+        if used >= limit.max_per_window {
+            return Err(CounterError::RateLimited);
+        }
+        
+        env.storage().temporary().set(&key, &(used + 1));
+        
+        // Only needs to live until its window closes
+        let sequence = env.ledger().sequence();
+        let window_end = (sequence / limit.window_ledgers + 1).saturating_mul(limit.window_ledgers);
+        let ttl = window_end - sequence;
+        env.storage().temporary().extend_ttl(&key, ttl, ttl);
+        Ok(())
+    }
+    
+    /// Append a change to the counter's ring buffer and publish it
+    fn record_change(env: &Env, counter: &CounterId, caller: &Address, op: CounterOp, new_value: u32) {
+        let record = ChangeRecord {
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            caller: caller.clone(),
+            op,
+            new_value,
+        };
+        
+        let len_key = DataKey::HistoryLen(counter.clone());
+        let index = Self::get_history_len(env.clone(), counter.clone());
+        let slot_key = DataKey::History(counter.clone(), index % HISTORY_CAPACITY);
+        
+        env.storage().persistent().set(&slot_key, &record);
+        env.storage().persistent().set(&len_key, &(index + 1));
+        for key in [slot_key, len_key] {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+        }
+        
+        CounterChanged { counter: counter.clone(), record }.publish(env);
+    }
+    
+    /// `count + delta`, kept inside [min, max] according to the policy.
+    /// i64 holds any u32 ± u32, so the arithmetic itself cannot overflow.
+    fn apply(config: &CounterConfig, count: u32, delta: i64) -> Result<u32, CounterError> {
+        let (min, max) = (config.min as i64, config.max as i64);
+        let target = count as i64 + delta;
+        
+        if (min..=max).contains(&target) {
+            return Ok(target as u32);
+        }
+        
+        let result = match config.policy {
+            OverflowPolicy::Saturate => target.clamp(min, max),
+            OverflowPolicy::Wrap => min + (target - min).rem_euclid(max - min + 1),
+            OverflowPolicy::Reject if target > max => return Err(CounterError::Overflow),
+            OverflowPolicy::Reject => return Err(CounterError::Underflow),
+        };
+        Ok(result as u32)
+    }
+    
+    /// Current value of a counter; reading an existing counter also extends its TTL
+    fn read_count(env: &Env, counter: &CounterId) -> u32 {
+        let key = DataKey::Counter(counter.clone());
+        if let Some(count) = env.storage().persistent().get::<DataKey, u32>(&key) {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+            count
+        } else {
+            0
+        }
+    }
+    
+    fn write_count(env: &Env, counter: &CounterId, count: u32) {
+        let key = DataKey::Counter(counter.clone());
+        env.storage().persistent().set(&key, &count);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, COUNTER_LIFETIME_THRESHOLD, COUNTER_BUMP_AMOUNT);
+    }
+    
     /// Extend the TTL of the contract instance storage
     /// This keeps the contract and its data alive on the blockchain
     fn extend_instance_ttl(env: &Env) {
@@ -143,19 +554,19 @@ mod test;
 ║                        STORAGE TYPES & TTL SUMMARY                           ║
 ╠══════════════════════════════════════════════════════════════════════════════╣
 ║                                                                              ║
-║  1. TEMPORARY Storage                                                        ║
+║  1. TEMPORARY Storage (What we're using for quota windows)                   ║
 ║     └─ Use: Session data, temporary calculations                             ║
 ║     └─ TTL: Very short (~24 hours)                                           ║
 ║     └─ Cost: Cheapest (no rent)                                              ║
 ║     └─ Example: Voting session, game state                                   ║
 ║                                                                              ║
-║  2. PERSISTENT Storage                                                       ║
+║  2. PERSISTENT Storage (What we're using for counters)                       ║
 ║     └─ Use: User balances, NFT ownership, long-term records                  ║
 ║     └─ TTL: Configurable, requires rent payment                              ║
 ║     └─ Cost: More expensive (pay-per-ledger)                                 ║
 ║     └─ Example: Token balances, user profiles                                ║
 ║                                                                              ║
-║  3. INSTANCE Storage (What we're using)                                      ║
+║  3. INSTANCE Storage (What we're using for roles)                            ║
 ║     └─ Use: Contract-wide settings, global state                             ║
 ║     └─ TTL: Tied to contract instance                                        ║
 ║     └─ Cost: Medium (bundled with contract)                                  ║
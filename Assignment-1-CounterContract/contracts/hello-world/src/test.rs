@@ -1,106 +1,617 @@
 #![cfg(test)]
 
-use crate::{CounterContract, CounterContractClient};
-use soroban_sdk::Env;
+use crate::{
+    ChangeRecord, CounterConfig, CounterContract, CounterContractClient, CounterError, CounterId,
+    CounterOp, DataKey, OverflowPolicy, RateLimit, COUNTER_BUMP_AMOUNT, DAY_IN_LEDGERS,
+    HISTORY_CAPACITY,
+};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{
+        storage::{Persistent as _, Temporary as _},
+        Address as _, Events, Ledger,
+    },
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+/// Deploys the counter and returns it together with its owner
+fn setup(env: &Env) -> (CounterContractClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let owner = Address::generate(env);
+    let contract_id = env.register(CounterContract, (&owner,));
+    let client = CounterContractClient::new(env, &contract_id);
+
+    (client, owner)
+}
+
+/// The shared counter most tests work on
+fn views() -> CounterId {
+    CounterId::Global(symbol_short!("views"))
+}
+
+/// Bounded config used by the policy tests
+fn bounded(min: u32, max: u32, step: u32, policy: OverflowPolicy) -> CounterConfig {
+    CounterConfig { min, max, step, policy }
+}
 
 #[test]
 fn test_increment() {
     let env = Env::default();
-    let contract_id = env.register(CounterContract, ());
-    let client = CounterContractClient::new(&env, &contract_id);
-    
+    let (client, owner) = setup(&env);
+
     // Initial count should be 0
-    assert_eq!(client.get_count(), 0);
-    
+    assert_eq!(client.get_count(&views()), 0);
+
     // Test incrementing
-    assert_eq!(client.increment(), 1);
-    assert_eq!(client.increment(), 2);
-    assert_eq!(client.increment(), 3);
-    
+    assert_eq!(client.increment(&owner, &views()), 1);
+    assert_eq!(client.increment(&owner, &views()), 2);
+    assert_eq!(client.increment(&owner, &views()), 3);
+
     // Verify final count
-    assert_eq!(client.get_count(), 3);
+    assert_eq!(client.get_count(&views()), 3);
 }
 
 #[test]
 fn test_decrement() {
     let env = Env::default();
-    let contract_id = env.register(CounterContract, ());
-    let client = CounterContractClient::new(&env, &contract_id);
-    
+    let (client, owner) = setup(&env);
+
     // First increment a few times
-    client.increment();
-    client.increment();
-    client.increment();
-    assert_eq!(client.get_count(), 3);
-    
+    client.increment(&owner, &views());
+    client.increment(&owner, &views());
+    client.increment(&owner, &views());
+    assert_eq!(client.get_count(&views()), 3);
+
     // Now decrement
-    assert_eq!(client.decrement(), 2);
-    assert_eq!(client.decrement(), 1);
-    assert_eq!(client.decrement(), 0);
-    
+    assert_eq!(client.decrement(&owner, &views()), 2);
+    assert_eq!(client.decrement(&owner, &views()), 1);
+    assert_eq!(client.decrement(&owner, &views()), 0);
+
     // Decrement at 0 should stay at 0 (no underflow)
-    assert_eq!(client.decrement(), 0);
+    assert_eq!(client.decrement(&owner, &views()), 0);
 }
 
 #[test]
 fn test_add() {
     let env = Env::default();
-    let contract_id = env.register(CounterContract, ());
-    let client = CounterContractClient::new(&env, &contract_id);
-    
+    let (client, owner) = setup(&env);
+
     // Note: In Soroban SDK, client methods take references for primitive types
-    assert_eq!(client.add(&5), 5);
-    assert_eq!(client.add(&10), 15);
-    assert_eq!(client.add(&20), 35);
-    
+    assert_eq!(client.add(&owner, &views(), &5), 5);
+    assert_eq!(client.add(&owner, &views(), &10), 15);
+    assert_eq!(client.add(&owner, &views(), &20), 35);
+
     // Verify final count
-    assert_eq!(client.get_count(), 35);
+    assert_eq!(client.get_count(&views()), 35);
 }
 
 #[test]
 fn test_reset() {
     let env = Env::default();
-    let contract_id = env.register(CounterContract, ());
-    let client = CounterContractClient::new(&env, &contract_id);
-    
+    let (client, owner) = setup(&env);
+
     // Increment a few times
-    client.increment();
-    client.increment();
-    assert_eq!(client.get_count(), 2);
-    
+    client.increment(&owner, &views());
+    client.increment(&owner, &views());
+    assert_eq!(client.get_count(&views()), 2);
+
     // Reset
-    client.reset();
-    assert_eq!(client.get_count(), 0);
-    
+    client.reset(&owner, &views());
+    assert_eq!(client.get_count(&views()), 0);
+
     // Increment again after reset
-    assert_eq!(client.increment(), 1);
+    assert_eq!(client.increment(&owner, &views()), 1);
 }
 
 #[test]
 fn test_get_count_initial() {
     let env = Env::default();
-    let contract_id = env.register(CounterContract, ());
-    let client = CounterContractClient::new(&env, &contract_id);
-    
+    let (client, _) = setup(&env);
+
     // Initial count should be 0
-    assert_eq!(client.get_count(), 0);
+    assert_eq!(client.get_count(&views()), 0);
 }
 
 #[test]
 fn test_combined_operations() {
     let env = Env::default();
-    let contract_id = env.register(CounterContract, ());
-    let client = CounterContractClient::new(&env, &contract_id);
-    
+    let (client, owner) = setup(&env);
+
     // Complex sequence of operations
-    client.increment();           // 1
-    client.increment();           // 2
-    client.add(&8);               // 10
-    client.decrement();           // 9
-    client.add(&1);               // 10
-    
-    assert_eq!(client.get_count(), 10);
-    
-    client.reset();               // 0
-    assert_eq!(client.get_count(), 0);
-}
\ No newline at end of file
+    client.increment(&owner, &views());           // 1
+    client.increment(&owner, &views());           // 2
+    client.add(&owner, &views(), &8);             // 10
+    client.decrement(&owner, &views());           // 9
+    client.add(&owner, &views(), &1);             // 10
+
+    assert_eq!(client.get_count(&views()), 10);
+
+    client.reset(&owner, &views());               // 0
+    assert_eq!(client.get_count(&views()), 0);
+}
+
+#[test]
+fn test_operator_roles() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    let operator = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(client.get_owner(), owner);
+
+    // Strangers cannot touch the counter
+    assert_eq!(client.try_increment(&stranger, &views()), Err(Ok(CounterError::Unauthorized)));
+    assert_eq!(client.try_reset(&stranger, &views()), Err(Ok(CounterError::Unauthorized)));
+
+    // Granting is signed by the owner
+    client.grant_operator(&operator);
+    assert_eq!(env.auths()[0].0, owner);
+    assert!(client.is_operator(&operator));
+
+    // The operator signs their own calls
+    assert_eq!(client.add(&operator, &views(), &4), 4);
+    assert_eq!(env.auths()[0].0, operator);
+
+    client.revoke_operator(&operator);
+    assert!(!client.is_operator(&operator));
+    assert_eq!(client.try_decrement(&operator, &views()), Err(Ok(CounterError::Unauthorized)));
+    assert_eq!(client.get_count(&views()), 4);
+}
+
+#[test]
+#[should_panic]
+fn test_grant_requires_owner_auth() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    // Drop the mocks: the owner has not signed
+    env.set_auths(&[]);
+    client.grant_operator(&Address::generate(&env));
+}
+
+#[test]
+#[should_panic]
+fn test_mutation_requires_caller_auth() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    // Being the owner is not enough without the owner's signature
+    env.set_auths(&[]);
+    client.increment(&owner, &views());
+}
+
+#[test]
+fn test_named_counters_are_independent() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let nonce = symbol_short!("nonce");
+    let alice_nonce = CounterId::User(alice.clone(), nonce.clone());
+    let bob_nonce = CounterId::User(bob, nonce.clone());
+    let global_nonce = CounterId::Global(nonce);
+
+    client.add(&owner, &views(), &10);
+    client.increment(&owner, &alice_nonce);
+    client.increment(&owner, &alice_nonce);
+    client.increment(&owner, &bob_nonce);
+
+    // Same name, different namespaces
+    assert_eq!(client.get_count(&views()), 10);
+    assert_eq!(client.get_count(&alice_nonce), 2);
+    assert_eq!(client.get_count(&bob_nonce), 1);
+    assert_eq!(client.get_count(&global_nonce), 0);
+
+    // Resetting one counter leaves the others alone
+    client.reset(&owner, &alice_nonce);
+    assert_eq!(client.get_count(&alice_nonce), 0);
+    assert_eq!(client.get_count(&bob_nonce), 1);
+    assert_eq!(client.get_count(&views()), 10);
+}
+
+#[test]
+fn test_counter_ttl() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    let quota = CounterId::User(Address::generate(&env), symbol_short!("quota"));
+
+    client.increment(&owner, &views());
+    client.increment(&owner, &quota);
+
+    let ttl = |counter: &CounterId| {
+        env.as_contract(&client.address, || {
+            env.storage().persistent().get_ttl(&DataKey::Counter(counter.clone()))
+        })
+    };
+    assert_eq!(ttl(&views()), COUNTER_BUMP_AMOUNT);
+
+    // Two days later only the counter that is touched gets extended
+    env.ledger().with_mut(|li| li.sequence_number += 2 * DAY_IN_LEDGERS);
+    client.extend_counter_ttl(&quota);
+    assert_eq!(ttl(&quota), COUNTER_BUMP_AMOUNT);
+    assert_eq!(ttl(&views()), COUNTER_BUMP_AMOUNT - 2 * DAY_IN_LEDGERS);
+}
+
+#[test]
+fn test_default_config_saturates_at_u32_bounds() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    assert_eq!(client.get_config(&views()), bounded(0, u32::MAX, 1, OverflowPolicy::Saturate));
+
+    // `add` used to panic here
+    client.add(&owner, &views(), &(u32::MAX - 1));
+    assert_eq!(client.add(&owner, &views(), &5), u32::MAX);
+    assert_eq!(client.increment(&owner, &views()), u32::MAX);
+}
+
+#[test]
+fn test_step_and_reset_to_min() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    client.configure(&views(), &bounded(10, 100, 5, OverflowPolicy::Saturate));
+    assert_eq!(env.auths()[0].0, owner);
+
+    // Configuring clamps the current value into range
+    assert_eq!(client.get_count(&views()), 10);
+    assert_eq!(client.increment(&owner, &views()), 15);
+    assert_eq!(client.decrement(&owner, &views()), 10);
+
+    // `add` ignores the step
+    assert_eq!(client.add(&owner, &views(), &3), 13);
+
+    client.reset(&owner, &views());
+    assert_eq!(client.get_count(&views()), 10);
+}
+
+#[test]
+fn test_saturate_policy() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    client.configure(&views(), &bounded(2, 10, 3, OverflowPolicy::Saturate));
+
+    assert_eq!(client.decrement(&owner, &views()), 2);
+    assert_eq!(client.add(&owner, &views(), &7), 9);
+    assert_eq!(client.increment(&owner, &views()), 10);
+    assert_eq!(client.increment(&owner, &views()), 10);
+}
+
+#[test]
+fn test_wrap_policy() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    // Range 0..=9 behaves like a single decimal digit
+    client.configure(&views(), &bounded(0, 9, 1, OverflowPolicy::Wrap));
+    assert_eq!(client.add(&owner, &views(), &9), 9);
+    assert_eq!(client.increment(&owner, &views()), 0);
+    assert_eq!(client.decrement(&owner, &views()), 9);
+    assert_eq!(client.add(&owner, &views(), &25), 4);
+
+    // A non-zero minimum wraps back to min, not to 0
+    let dial = CounterId::Global(symbol_short!("dial"));
+    client.configure(&dial, &bounded(5, 7, 2, OverflowPolicy::Wrap));
+    assert_eq!(client.increment(&owner, &dial), 7);
+    assert_eq!(client.increment(&owner, &dial), 6);
+    assert_eq!(client.decrement(&owner, &dial), 7);
+
+    // The full u32 range wraps like plain wrapping arithmetic
+    let raw = CounterId::Global(symbol_short!("raw"));
+    client.configure(&raw, &bounded(0, u32::MAX, 1, OverflowPolicy::Wrap));
+    assert_eq!(client.decrement(&owner, &raw), u32::MAX);
+    assert_eq!(client.increment(&owner, &raw), 0);
+}
+
+#[test]
+fn test_reject_policy() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    client.configure(&views(), &bounded(0, 10, 4, OverflowPolicy::Reject));
+
+    assert_eq!(client.try_decrement(&owner, &views()), Err(Ok(CounterError::Underflow)));
+    assert_eq!(client.add(&owner, &views(), &10), 10);
+    assert_eq!(client.try_increment(&owner, &views()), Err(Ok(CounterError::Overflow)));
+    assert_eq!(client.try_add(&owner, &views(), &u32::MAX), Err(Ok(CounterError::Overflow)));
+
+    // Failed calls leave the value untouched
+    assert_eq!(client.get_count(&views()), 10);
+    assert_eq!(client.decrement(&owner, &views()), 6);
+}
+
+#[test]
+fn test_invalid_config() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    assert_eq!(
+        client.try_configure(&views(), &bounded(5, 4, 1, OverflowPolicy::Saturate)),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_configure(&views(), &bounded(0, 4, 0, OverflowPolicy::Saturate)),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_configure_requires_owner_auth() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    env.set_auths(&[]);
+    client.configure(&views(), &bounded(0, 10, 1, OverflowPolicy::Reject));
+}
+
+#[test]
+fn test_rate_limited_quota() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let api = CounterId::Global(symbol_short!("api"));
+
+    assert_eq!(client.get_remaining_quota(&api, &alice), None);
+
+    // Quota mode opens increment to any signer, 2 per 100 ledgers each
+    env.ledger().with_mut(|li| li.sequence_number = 1_050);
+    client.set_rate_limit(&api, &RateLimit { max_per_window: 2, window_ledgers: 100 });
+    assert_eq!(client.get_remaining_quota(&api, &alice), Some(2));
+
+    assert_eq!(client.increment(&alice, &api), 1);
+    assert_eq!(env.auths()[0].0, alice);
+    assert_eq!(client.get_remaining_quota(&api, &alice), Some(1));
+    assert_eq!(client.increment(&alice, &api), 2);
+    assert_eq!(client.try_increment(&alice, &api), Err(Ok(CounterError::RateLimited)));
+
+    // Quotas are per address, and the owner is limited too
+    assert_eq!(client.get_remaining_quota(&api, &bob), Some(2));
+    assert_eq!(client.increment(&bob, &api), 3);
+    client.increment(&owner, &api);
+    client.increment(&owner, &api);
+    assert_eq!(client.try_increment(&owner, &api), Err(Ok(CounterError::RateLimited)));
+
+    // Still inside the window at its last ledger
+    env.ledger().with_mut(|li| li.sequence_number = 1_099);
+    assert_eq!(client.get_remaining_quota(&api, &alice), Some(0));
+
+    // A new window starts with a fresh quota
+    env.ledger().with_mut(|li| li.sequence_number = 1_100);
+    assert_eq!(client.get_remaining_quota(&api, &alice), Some(2));
+    assert_eq!(client.increment(&alice, &api), 6);
+
+    // Other mutations still need the operator role
+    assert_eq!(client.try_reset(&alice, &api), Err(Ok(CounterError::Unauthorized)));
+
+    // Without the limit only operators may increment again
+    client.clear_rate_limit(&api);
+    assert_eq!(client.get_remaining_quota(&api, &alice), None);
+    assert_eq!(client.try_increment(&alice, &api), Err(Ok(CounterError::Unauthorized)));
+}
+
+#[test]
+fn test_quota_window_lives_in_temporary_storage() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let api = CounterId::Global(symbol_short!("api"));
+
+    env.ledger().with_mut(|li| li.sequence_number = 1_000);
+    client.set_rate_limit(&api, &RateLimit { max_per_window: 5, window_ledgers: 500 });
+    env.ledger().with_mut(|li| li.sequence_number = 1_200);
+    client.increment(&alice, &api);
+
+    // The usage entry only has to outlive the window (ledgers 1000..1500)
+    let key = DataKey::Usage(api.clone(), alice.clone(), 2);
+    let ttl = env.as_contract(&client.address, || env.storage().temporary().get_ttl(&key));
+    assert_eq!(ttl, 300);
+}
+
+#[test]
+fn test_rate_limit_ttl() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let api = CounterId::Global(symbol_short!("api"));
+    let idle = CounterId::Global(symbol_short!("idle"));
+    client.set_rate_limit(&api, &RateLimit { max_per_window: 5, window_ledgers: 100 });
+    client.set_rate_limit(&idle, &RateLimit { max_per_window: 5, window_ledgers: 100 });
+    client.configure(&idle, &bounded(0, 10, 1, OverflowPolicy::Saturate));
+
+    let ttl = |key: DataKey| env.as_contract(&client.address, || env.storage().persistent().get_ttl(&key));
+
+    // Two days later, using the quota keeps the limit alive
+    env.ledger().with_mut(|li| li.sequence_number += 2 * DAY_IN_LEDGERS);
+    client.increment(&alice, &api);
+    assert_eq!(ttl(DataKey::RateLimit(api.clone())), COUNTER_BUMP_AMOUNT);
+    assert_eq!(ttl(DataKey::RateLimit(idle.clone())), COUNTER_BUMP_AMOUNT - 2 * DAY_IN_LEDGERS);
+
+    // extend_counter_ttl covers the config and rate limit of an unused counter
+    client.extend_counter_ttl(&idle);
+    assert_eq!(ttl(DataKey::RateLimit(idle.clone())), COUNTER_BUMP_AMOUNT);
+    assert_eq!(ttl(DataKey::Config(idle)), COUNTER_BUMP_AMOUNT);
+}
+
+#[test]
+fn test_invalid_rate_limit() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    assert_eq!(
+        client.try_set_rate_limit(&views(), &RateLimit { max_per_window: 0, window_ledgers: 10 }),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_rate_limit(&views(), &RateLimit { max_per_window: 1, window_ledgers: 0 }),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+}
+
+#[test]
+fn test_rate_limit_window_capped_at_max_ttl() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let alice = Address::generate(&env);
+    let api = CounterId::Global(symbol_short!("api"));
+    let max_ttl = env.as_contract(&client.address, || env.storage().max_ttl());
+
+    // A longer window could never be covered by the usage entry's TTL
+    assert_eq!(
+        client.try_set_rate_limit(&api, &RateLimit { max_per_window: 1, window_ledgers: max_ttl + 1 }),
+        Err(Ok(CounterError::InvalidConfig))
+    );
+
+    // The longest allowed window still counts increments
+    client.set_rate_limit(&api, &RateLimit { max_per_window: 1, window_ledgers: max_ttl });
+    assert_eq!(client.increment(&alice, &api), 1);
+    assert_eq!(client.try_increment(&alice, &api), Err(Ok(CounterError::RateLimited)));
+}
+
+#[test]
+fn test_change_history() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+    let operator = Address::generate(&env);
+    client.grant_operator(&operator);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 100;
+        li.timestamp = 1_000;
+    });
+    client.increment(&owner, &views());
+    client.add(&operator, &views(), &5);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 110;
+        li.timestamp = 1_050;
+    });
+    client.decrement(&operator, &views());
+    client.reset(&owner, &views());
+
+    let record = |ledger, timestamp, caller: &Address, op, new_value| ChangeRecord {
+        ledger,
+        timestamp,
+        caller: caller.clone(),
+        op,
+        new_value,
+    };
+    assert_eq!(client.get_history_len(&views()), 4);
+    assert_eq!(
+        client.get_history(&views(), &0, &10),
+        vec![
+            &env,
+            record(100, 1_000, &owner, CounterOp::Increment, 1),
+            record(100, 1_000, &operator, CounterOp::Add, 6),
+            record(110, 1_050, &operator, CounterOp::Decrement, 5),
+            record(110, 1_050, &owner, CounterOp::Reset, 0),
+        ]
+    );
+
+    // Pages
+    let page = client.get_history(&views(), &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().op, CounterOp::Add);
+    assert_eq!(page.get(1).unwrap().op, CounterOp::Decrement);
+    assert_eq!(client.get_history(&views(), &4, &10).len(), 0);
+
+    // Each counter has its own history; rejected calls record nothing
+    assert_eq!(client.get_history_len(&CounterId::Global(symbol_short!("other"))), 0);
+    let _ = client.try_increment(&Address::generate(&env), &views());
+    assert_eq!(client.get_history_len(&views()), 4);
+}
+
+#[test]
+fn test_history_ring_buffer_keeps_latest() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    let total = HISTORY_CAPACITY + 10;
+    for _ in 0..total {
+        client.increment(&owner, &views());
+    }
+    assert_eq!(client.get_history_len(&views()), total);
+
+    // The first 10 changes were overwritten, so reading from 0 starts at change 10
+    let history = client.get_history(&views(), &0, &u32::MAX);
+    assert_eq!(history.len(), HISTORY_CAPACITY);
+    assert_eq!(history.first().unwrap().new_value, 11);
+    assert_eq!(history.last().unwrap().new_value, total);
+
+    let tail = client.get_history(&views(), &(total - 3), &10);
+    assert_eq!(tail.len(), 3);
+    assert_eq!(tail.first().unwrap().new_value, total - 2);
+}
+
+#[test]
+fn test_configure_records_clamp() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    client.add(&owner, &views(), &50);
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 200;
+        li.timestamp = 2_000;
+    });
+    client.configure(&views(), &bounded(0, 10, 1, OverflowPolicy::Saturate));
+
+    // Clamping 50 down to 10 shows up in the history and as an event
+    let clamp = ChangeRecord {
+        ledger: 200,
+        timestamp: 2_000,
+        caller: owner.clone(),
+        op: CounterOp::Configure,
+        new_value: 10,
+    };
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "counter_changed"), views()).into_val(&env),
+                clamp.clone().into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.get_history_len(&views()), 2);
+    assert_eq!(client.get_history(&views(), &1, &1), vec![&env, clamp]);
+
+    // Bounds that already contain the value record nothing
+    client.configure(&views(), &bounded(5, 20, 1, OverflowPolicy::Saturate));
+    assert_eq!(client.get_count(&views()), 10);
+    assert_eq!(client.get_history_len(&views()), 2);
+}
+
+#[test]
+fn test_change_events() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 42;
+        li.timestamp = 4_200;
+    });
+    client.add(&owner, &views(), &7);
+
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "counter_changed"), views()).into_val(&env),
+                ChangeRecord {
+                    ledger: 42,
+                    timestamp: 4_200,
+                    caller: owner.clone(),
+                    op: CounterOp::Add,
+                    new_value: 7,
+                }
+                .into_val(&env),
+            ),
+        ]
+    );
+}
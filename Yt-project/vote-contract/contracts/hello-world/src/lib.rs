@@ -1,17 +1,53 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, 
-    symbol_short, Address, Env, String, Symbol,
+    contract, contracterror, contractevent, contractimpl, contracttype, 
+    symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
 };
 
 // ═══════════════════════════════════════════════════════════════════════════════
 //                              STORAGE KEYS
 // ═══════════════════════════════════════════════════════════════════════════════
 
-const TOTAL_YES: Symbol = symbol_short!("yes");      // Total YES votes
-const TOTAL_NO: Symbol = symbol_short!("no");        // Total NO votes
-const TOTAL_VOTES: Symbol = symbol_short!("total");  // Total votes overall
+const PROPOSAL_COUNT: Symbol = symbol_short!("prop_cnt"); // Number of proposals created
+const ADMIN: Symbol = symbol_short!("admin");              // Manages the voter registry
+const REGISTERED_COUNT: Symbol = symbol_short!("reg_cnt");  // Size of the voter registry
+
+// Everything that belongs to one poll is keyed by its proposal id,
+// so a single deployment can run any number of polls side by side.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Proposal(u32),           // Proposal metadata
+    Tally(u32),              // Map<option, votes> for the poll
+    TotalVotes(u32),         // Sum of all votes cast in the poll
+    BallotCount(u32),        // Number of VoterRecords in the poll (for quorum)
+    Voter(u32, Address),     // VoterRecord for (proposal_id, voter)
+    Delegation(u32, Address), // Who (proposal_id, from) hands their vote to
+    Delegators(u32, Address), // Vec of direct delegators of (proposal_id, to)
+    Commitment(u32, Address), // Secret ballot hash for (proposal_id, voter)
+    Committers(u32),          // Vec of everyone who committed in the poll
+    Registered(Address),      // Present while the admin lists the address as eligible
+    VoterIndexLen(u32),       // Number of addresses in the poll's voter index
+    VoterAt(u32, u32),        // Address at (proposal_id, position) of the voter index
+    VoterSlot(u32, Address),  // Position of (proposal_id, voter) in the voter index
+    Result(u32),              // PollResult written once by finalize
+    Locked(u32, Address),     // Tokens held for (proposal_id, holder) in a token-weighted poll
+    Rankings(u32),            // Map<ranking as option positions, votes> of a RankedChoice poll
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              BALLOT LIMITS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const MIN_OPTIONS: u32 = 2;   // A poll needs something to choose between
+const MAX_OPTIONS: u32 = 20;  // Keeps the tally map and ranking cheap
+const MAX_RANKED_OPTIONS: u32 = 5; // At most 325 distinct rankings to run the runoff over
+const MAX_STANDARD_VOTES: u64 = 5; // Per-voter cap in VotingMode::Standard
+const MAX_DELEGATION_DEPTH: u32 = 4; // Longest allowed chain a → b → c → d → e
+const MAX_DELEGATORS: u32 = 8;      // Ballots one vote may cast for others (ledger write limit)
+const MAX_PROOF_LENGTH: u32 = 32;   // Merkle depth; 2^32 leaves is plenty
+const MAX_PAGE_SIZE: u32 = 100;     // Records returned by one list_voters call
 
 // ═══════════════════════════════════════════════════════════════════════════════
 //                              TTL CONSTANTS
@@ -21,8 +57,6 @@ const TOTAL_VOTES: Symbol = symbol_short!("total");  // Total votes overall
 // ~120,960 ledgers ≈ 1 week
 // ~518,400 ledgers ≈ 1 month
 
-const DAY_IN_LEDGERS: u32 = 17_280;
-const WEEK_IN_LEDGERS: u32 = 120_960;
 const MONTH_IN_LEDGERS: u32 = 518_400;
 const YEAR_IN_LEDGERS: u32 = 6_307_200;
 
@@ -30,10 +64,209 @@ const YEAR_IN_LEDGERS: u32 = 6_307_200;
 const VOTER_RECORD_TTL: u32 = YEAR_IN_LEDGERS;           // 1 year
 const VOTER_RECORD_TTL_THRESHOLD: u32 = MONTH_IN_LEDGERS; // Extend if less than 1 month left
 
-// TTL for instance data (global totals)
+// TTL for proposal data (metadata + totals of each poll)
+const PROPOSAL_TTL: u32 = YEAR_IN_LEDGERS;               // 1 year
+const PROPOSAL_TTL_THRESHOLD: u32 = MONTH_IN_LEDGERS;    // Extend if less than 1 month left
+
+// TTL for instance data (proposal counter)
 const INSTANCE_TTL: u32 = YEAR_IN_LEDGERS;               // 1 year
 const INSTANCE_TTL_THRESHOLD: u32 = MONTH_IN_LEDGERS;    // Extend if less than 1 month left
 
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              ERRORS
+// ═══════════════════════════════════════════════════════════════════════════════
+// Returned to the caller as a numeric code (e.g. Error(Contract, #4)),
+// so frontends can match on the code instead of a panic message.
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VoteError {
+    TooFewVotes = 1,      // vote_count < 1
+    TooManyVotes = 2,     // vote_count > 5 (standard mode)
+    InvalidChoice = 3,    // choice is not one of the poll's options
+    AlreadyVoted = 4,     // voter already has a VoterRecord
+    VoterNotFound = 5,    // no VoterRecord for this address
+    ProposalNotFound = 6, // no proposal with this id
+    InvalidWindow = 7,    // end_time is not after start_time
+    PollNotOpen = 8,      // voting has not started yet
+    PollClosed = 9,       // voting has already ended
+    PollNotClosed = 10,   // final result requested before end_time
+    InvalidOptions = 11,  // too few/many options, or a duplicate option
+    InsufficientCredits = 12, // quadratic cost exceeds the credit budget
+    InsufficientVotingPower = 13, // vote_count exceeds the voter's token balance
+    AlreadyDelegated = 14, // voter handed their vote to someone else
+    NotDelegated = 15,    // undelegate without an active delegation
+    DelegationCycle = 16, // delegation would lead back to the delegator
+    DelegationTooDeep = 17, // chain would exceed MAX_DELEGATION_DEPTH
+    DelegateAlreadyVoted = 18, // the delegate's ballot is already cast
+    CastByDelegate = 19,  // ballot was cast by a delegate; undelegate instead
+    Unauthorized = 20,    // only the creator may close a poll early
+    SecretBallot = 21,    // not available in a commit-reveal poll
+    NotSecretBallot = 22, // commit/reveal used on an open-ballot poll
+    NotRevealPhase = 23,  // reveal outside [end_time, reveal_end_time)
+    CommitmentNotFound = 24, // reveal without a prior commit
+    CommitmentMismatch = 25, // sha256(choice ‖ vote_count ‖ salt) differs from the commit
+    ResultsHidden = 26,   // tallies of a secret poll before reveals are done
+    NotEligible = 27,     // voter is not on the admin's registry
+    InvalidProof = 28,    // Merkle proof does not lead to the poll's root
+    InvalidQuorum = 29,   // percent outside 1-100, or a percent quorum without an electorate
+    AlreadyFinalized = 30, // finalize was already called for this poll
+    NotFinalized = 31,    // no PollResult stored yet
+    RankedBallot = 32,    // single-choice call on a ranked-choice poll
+    NotRankedChoice = 33, // vote_ranked on a poll that is not ranked-choice
+    InvalidRanking = 34,  // empty ranking, or an option listed twice
+    Overflow = 35,        // a tally or the poll total would exceed u64::MAX
+    NotQuadratic = 36,    // vote_quadratic on a poll that is not quadratic
+    SplitBallot = 37,     // change_vote on a split ballot; retract and vote again
+    NothingLocked = 38,   // withdraw_tokens without tokens held for the holder
+    TooManyDelegators = 39, // the chain's top would cast more than MAX_DELEGATORS ballots
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         VOTING MODES
+// ═══════════════════════════════════════════════════════════════════════════════
+// Standard:          cast 1-5 votes, no cost
+// Quadratic(b):      every voter has a budget of b credits; n votes cost n² credits
+//                    (budget 25 → at most 5 votes, budget 100 → at most 10 votes).
+//                    vote_quadratic spreads one ballot over several options for
+//                    the sum of the squares ({yes: 6, no: 8} costs 36 + 64 = 100).
+// TokenWeighted(t):  voting power = tokens of SEP-41 token t that the voter
+//                    locks in the contract. Voting transfers vote_count tokens
+//                    in; delegating transfers the delegator's whole balance in.
+//                    They come back on retract/undelegate, or through
+//                    withdraw_tokens once the poll is over, so the same tokens
+//                    cannot be moved to a fresh address and counted twice.
+// RankedChoice:      one ballot per voter listing options in order of
+//                    preference (vote_ranked). get_winner runs an instant
+//                    runoff; the tally only shows first preferences.
+//                    Not combinable with secret ballots; at most 5 options.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VotingMode {
+    Standard,
+    Quadratic(u64),
+    TokenWeighted(Address),
+    RankedChoice,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         ELIGIBILITY
+// ═══════════════════════════════════════════════════════════════════════════════
+// Anyone:        every address that can sign may vote
+// Registered:    only addresses the admin added with register_voter(s).
+//                The registry is shared by all polls; revoking an address
+//                blocks it from casting (or delegating) in every such poll.
+// MerkleRoot(r, n): only addresses that are leaves of the Merkle tree with
+//                root r; n is the number of leaves (used for percent quorums).
+//                Nothing is written per voter up front: each voter brings
+//                their own proof to vote_with_proof / commit_vote_with_proof.
+//
+// Merkle tree layout (built off-chain, checked with env.crypto().sha256):
+//   leaf   = sha256(voter.to_xdr())
+//   parent = sha256(min(a, b) ‖ max(a, b))   (sorted pair, so no left/right flags)
+// A proof is the list of sibling hashes from the leaf up to the root.
+// Delegators carry no proof, so delegation is not available in Merkle polls.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Eligibility {
+    Anyone,
+    Registered,
+    MerkleRoot(BytesN<32>, u32),
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         QUORUM & THRESHOLD
+// ═══════════════════════════════════════════════════════════════════════════════
+// Quorum counts ballots (one per voter, delegated ones included):
+//   None:        no minimum turnout
+//   Ballots(n):  at least n ballots
+//   Percent(p):  at least p% of the electorate, which is the registry size
+//                (Registered) or the leaf count (MerkleRoot) when the poll
+//                is created. Later registry changes do not move the bar.
+//                Not allowed for Anyone polls, or an empty electorate.
+//
+// Threshold is the share of all votes the leading option needs:
+//   Plurality       most votes (the old behaviour)
+//   SimpleMajority  more than 1/2
+//   TwoThirds       at least 2/3
+//   Unanimity       every vote
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Quorum {
+    None,
+    Ballots(u32),
+    Percent(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Threshold {
+    Plurality,
+    SimpleMajority,
+    TwoThirds,
+    Unanimity,
+}
+
+/// What get_winner reports once a poll is over
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PollOutcome {
+    Passed(String),          // The leading option met the quorum and threshold
+    Tie(Vec<String>),        // Several options share the most votes
+    ThresholdNotMet(String), // The leader fell short of the threshold
+    QuorumNotMet,            // Too few ballots for any result to count
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         PROPOSAL
+// ═══════════════════════════════════════════════════════════════════════════════
+// Times are ledger timestamps (seconds since Unix epoch).
+// Voting is open while start_time <= now < end_time.
+// Secret polls add a reveal phase: end_time <= now < reveal_end_time.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalConfig {
+    pub title: String,          // What is being voted on
+    pub options: Vec<String>,   // Choices on the ballot (2-20, unique)
+    pub mode: VotingMode,       // How vote_count is limited and priced
+    pub start_time: u64,        // First second votes are accepted
+    pub end_time: u64,          // Votes are rejected from this second on
+    pub reveal_end_time: Option<u64>, // Some(t): commit-reveal poll, reveals until t
+    pub eligibility: Eligibility, // Who may cast a ballot
+    pub quorum: Quorum,         // Minimum turnout for a valid result
+    pub threshold: Threshold,   // Share of the votes the winner needs
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u32,                 // Sequential id, starting at 1
+    pub creator: Address,        // Who opened the poll
+    pub config: ProposalConfig,  // Settings chosen by the creator
+    pub created_at: u64,         // Ledger timestamp at creation
+    pub closed: bool,            // Set by close_poll; no more ballots after this
+    pub electorate: u32,         // Eligible voters at creation (0 for Anyone polls)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         RANKED RESULT
+// ═══════════════════════════════════════════════════════════════════════════════
+// Options with equal votes share a rank, and the next rank is skipped
+// ("1, 1, 3" ranking), so a three-way tie for first shows as three rank-1 rows.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RankedOption {
+    pub rank: u32,         // 1 = most votes
+    pub option: String,    // Option as written in the ProposalConfig
+    pub votes: u64,        // Votes received
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 //                         VOTER RECORD
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -41,10 +274,182 @@ const INSTANCE_TTL_THRESHOLD: u32 = MONTH_IN_LEDGERS;    // Extend if less than
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VoterRecord {
+    pub proposal_id: u32,  // Which poll this ballot belongs to
     pub voter: Address,    // Real Stellar address
-    pub choice: String,    // "yes" or "no"
-    pub votes: u64,        // Number of votes (1-5)
+    pub choice: String,    // One of the poll's options (the largest share of a split)
+    pub votes: u64,        // Weight actually counted (1-5, credits or tokens; a split's sum)
+    pub credits_spent: u64, // Sum of votes² per option in quadratic mode, 0 otherwise
     pub timestamp: u64,    // When they voted
+    pub cast_by: Address,  // The voter, or the delegate who voted for them
+    pub ranking: Vec<String>, // RankedChoice: all preferences, choice first; else empty
+    pub allocation: Map<String, u64>, // vote_quadratic: votes per option; else empty
+    pub live_until_ledger: u32, // Last ledger before archival; set by every write and extension
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         INSTANT RUNOFF
+// ═══════════════════════════════════════════════════════════════════════════════
+// Each round counts every ballot for its highest-ranked option that is still
+// in the race. An option with more than half of those votes wins; otherwise
+// the option(s) with the fewest votes are eliminated and the next round
+// starts. Ballots whose options are all eliminated are exhausted and no
+// longer count. If every remaining option ties for last, nobody wins.
+//
+// Ballots are added up per distinct ranking as they are cast, so the runoff
+// reads one map instead of every ballot and costs the same for any number
+// of voters. Ranked polls take at most MAX_RANKED_OPTIONS options, which
+// bounds that map.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunoffRound {
+    pub counts: Map<String, u64>,  // Votes per option still in the race
+    pub eliminated: Vec<String>,   // Dropped after this round (empty in the last)
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunoffReport {
+    pub rounds: Vec<RunoffRound>,  // In order, first preferences first
+    pub winner: Option<String>,    // None when the last options tie
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         FINAL RESULT
+// ═══════════════════════════════════════════════════════════════════════════════
+// Written once by finalize and never touched again. It carries everything
+// needed to read the result without the live counters (tally, totals,
+// ballot count) and records exactly when the snapshot was taken.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PollResult {
+    pub proposal_id: u32,          // Which poll this is the result of
+    pub tally: Map<String, u64>,   // Votes per option
+    pub total_votes: u64,          // Sum of all votes
+    pub ballot_count: u32,         // Ballots cast, delegated ones included
+    pub outcome: PollOutcome,      // Same as get_winner at finalization
+    pub ledger_sequence: u32,      // Ledger in which finalize ran
+    pub timestamp: u64,            // Ledger timestamp of finalize
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                         VOTER RECORD TTL
+// ═══════════════════════════════════════════════════════════════════════════════
+// Contracts cannot read an entry's TTL, so each VoterRecord carries its own
+// live-until ledger, updated with the same threshold rule the host applies on
+// every extend_ttl. The record is only rewritten when the host actually
+// extends it, so keeping the copy costs no extra entry.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordTtl {
+    pub voter: Address,          // Whose record this is
+    pub live_until_ledger: u32,  // Last ledger before the record is archived
+    pub ttl: u32,                // Ledgers left from the current one
+    pub near_archival: bool,     // ttl below the extension threshold (1 month)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                              EVENTS
+// ═══════════════════════════════════════════════════════════════════════════════
+// Indexers can rebuild every tally from these without polling get_results:
+//
+//   topics                                   data
+//   ["vote_committed", proposal_id, voter]   BytesN<32>    (secret polls; the hash only)
+//   ["vote_cast", proposal_id, voter]        VoterRecord   (one per ballot, incl. delegated)
+//   ["vote_changed", proposal_id, voter]     VoteChange
+//   ["vote_retracted", proposal_id, voter]   VoterRecord   (the ballot that was removed)
+//   ["ttl_extended"]                         TtlExtension
+//   ["poll_closed", proposal_id]             PollSummary
+//   ["poll_finalized", proposal_id]          PollResult
+
+/// Payload of `vote_changed`: the ballot after the change, plus what it was before
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteChange {
+    pub previous_choice: String,   // Option the weight was moved away from
+    pub record: VoterRecord,       // Ballot as stored after the change
+}
+
+/// Payload of `ttl_extended`: which entry was kept alive and for how long
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlExtension {
+    pub proposal_id: Option<u32>,  // None for the contract instance
+    pub voter: Option<Address>,    // Set when a voter record was extended, None for a whole poll
+    pub extend_to: u32,            // Target TTL in ledgers
+}
+
+/// Payload of `poll_closed`: the tally at the moment voting stopped
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PollSummary {
+    pub tally: Map<String, u64>,   // Votes per option
+    pub total_votes: u64,          // Sum of all votes
+    pub closed_at: u64,            // Ledger timestamp of close_poll
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteCommitted {
+    #[topic]
+    pub proposal_id: u32,
+    #[topic]
+    pub voter: Address,
+    pub commitment: BytesN<32>,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteCast {
+    #[topic]
+    pub proposal_id: u32,
+    #[topic]
+    pub voter: Address,
+    pub record: VoterRecord,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteChanged {
+    #[topic]
+    pub proposal_id: u32,
+    #[topic]
+    pub voter: Address,
+    pub change: VoteChange,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteRetracted {
+    #[topic]
+    pub proposal_id: u32,
+    #[topic]
+    pub voter: Address,
+    pub record: VoterRecord,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlExtended {
+    pub extension: TtlExtension,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PollClosed {
+    #[topic]
+    pub proposal_id: u32,
+    pub summary: PollSummary,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PollFinalized {
+    #[topic]
+    pub proposal_id: u32,
+    pub result: PollResult,
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -57,304 +462,3623 @@ pub struct VoteContract;
 #[contractimpl]
 impl VoteContract {
     
-    // ─────────────────────────────────────────────────────────────────────────
-    //                     MAIN VOTING FUNCTION
-    // ─────────────────────────────────────────────────────────────────────────
-    pub fn vote(env: Env, voter: Address, choice: String, vote_count: u64) {
-        // ═══════════════════════════════════════════════════════════════════
-        //              AUTHENTICATION
-        // ═══════════════════════════════════════════════════════════════════
-        voter.require_auth();
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     CONSTRUCTOR
+    // ─────────────────────────────────────────────────────────────────────────
+    
+    /// Runs once at deploy time: stores the admin of the voter registry
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL);
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     VOTER REGISTRY (ADMIN)
+    // ─────────────────────────────────────────────────────────────────────────
+    // Registrations are persistent entries with the voter-record TTL, so a
+    // registered voter stays eligible for polls created much later.
+    
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&ADMIN).unwrap()
+    }
+    
+    /// Add one address to the registry (registering twice is a no-op)
+    pub fn register_voter(env: Env, voter: Address) {
+        Self::get_admin(env.clone()).require_auth();
+        Self::add_registration(&env, voter);
+    }
+    
+    /// Add a batch of addresses with a single admin signature
+    pub fn register_voters(env: Env, voters: Vec<Address>) {
+        Self::get_admin(env.clone()).require_auth();
+        for voter in voters.iter() {
+            Self::add_registration(&env, voter);
+        }
+    }
+    
+    /// Remove an address from the registry. Ballots it already cast stay counted.
+    pub fn revoke_voter(env: Env, voter: Address) {
+        Self::get_admin(env.clone()).require_auth();
+        
+        let key = DataKey::Registered(voter);
+        if env.storage().persistent().has(&key) {
+            env.storage().persistent().remove(&key);
+            let count = Self::get_registered_count(env.clone());
+            env.storage().instance().set(&REGISTERED_COUNT, &(count - 1));
+        }
+    }
+    
+    pub fn is_registered(env: Env, voter: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Registered(voter))
+    }
+    
+    /// Number of addresses currently in the registry
+    pub fn get_registered_count(env: Env) -> u32 {
+        env.storage().instance().get(&REGISTERED_COUNT).unwrap_or(0)
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     PROPOSAL MANAGEMENT
+    // ─────────────────────────────────────────────────────────────────────────
+    
+    /// Create a new poll and return its id
+    pub fn create_proposal(env: Env, creator: Address, config: ProposalConfig) -> Result<u32, VoteError> {
+        creator.require_auth();
+        
+        if config.end_time <= config.start_time {
+            return Err(VoteError::InvalidWindow);
+        }
+        if let Some(reveal_end_time) = config.reveal_end_time {
+            if reveal_end_time <= config.end_time {
+                return Err(VoteError::InvalidWindow);
+            }
+            if config.mode == VotingMode::RankedChoice {
+                return Err(VoteError::RankedBallot);
+            }
+        }
+        
+        // Percent quorums are measured against this snapshot
+        let electorate = match &config.eligibility {
+            Eligibility::Anyone => 0,
+            Eligibility::Registered => Self::get_registered_count(env.clone()),
+            Eligibility::MerkleRoot(_, leaves) => *leaves,
+        };
+        if let Quorum::Percent(percent) = config.quorum {
+            if electorate == 0 || !(1..=100).contains(&percent) {
+                return Err(VoteError::InvalidQuorum);
+            }
+        }
+        
+        let option_count = config.options.len();
+        if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&option_count) {
+            return Err(VoteError::InvalidOptions);
+        }
+        if config.mode == VotingMode::RankedChoice && option_count > MAX_RANKED_OPTIONS {
+            return Err(VoteError::InvalidOptions);
+        }
+        
+        // Every option starts with 0 votes, which also rejects duplicates
+        let mut tally: Map<String, u64> = Map::new(&env);
+        for option in config.options.iter() {
+            if tally.contains_key(option.clone()) {
+                return Err(VoteError::InvalidOptions);
+            }
+            tally.set(option, 0);
+        }
+        
+        let id: u32 = env.storage().instance().get(&PROPOSAL_COUNT).unwrap_or(0) + 1;
+        env.storage().instance().set(&PROPOSAL_COUNT, &id);
+        
+        let proposal = Proposal {
+            id,
+            creator,
+            config,
+            created_at: env.ledger().timestamp(),
+            closed: false,
+            electorate,
+        };
+        
+        let key = DataKey::Proposal(id);
+        env.storage().persistent().set(&key, &proposal);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+        
+        let tally_key = DataKey::Tally(id);
+        env.storage().persistent().set(&tally_key, &tally);
+        env.storage().persistent().extend_ttl(&tally_key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+        
+        env.storage().instance().extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL);
+        
+        Ok(id)
+    }
+    
+    /// Stop accepting ballots and publish the closing tally.
+    /// The creator may close early; after end_time anyone may call it.
+    pub fn close_poll(env: Env, caller: Address, proposal_id: u32) -> Result<(), VoteError> {
+        caller.require_auth();
+        
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if proposal.closed {
+            return Err(VoteError::PollClosed);
+        }
+        
+        // A secret poll's tally only becomes final after the reveal phase
+        Self::require_results_visible(&env, &proposal)?;
+        
+        let now = env.ledger().timestamp();
+        if now < proposal.config.end_time {
+            if caller != proposal.creator {
+                return Err(VoteError::Unauthorized);
+            }
+            // Closing early: the window ends now
+            proposal.config.end_time = now;
+        }
+        
+        proposal.closed = true;
+        let key = DataKey::Proposal(proposal_id);
+        env.storage().persistent().set(&key, &proposal);
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        PollClosed {
+            proposal_id,
+            summary: PollSummary {
+                tally: Self::read_tally(&env, proposal_id),
+                total_votes: Self::read_total_votes(&env, proposal_id),
+                closed_at: now,
+            },
+        }
+        .publish(&env);
+        
+        Ok(())
+    }
+    
+    /// Freeze the outcome of a finished poll into a persistent PollResult.
+    /// Anyone may call it once get_winner is available; the poll is marked
+    /// closed, so every later ballot call fails with PollClosed.
+    pub fn finalize(env: Env, proposal_id: u32) -> Result<PollResult, VoteError> {
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        
+        let key = DataKey::Result(proposal_id);
+        if env.storage().persistent().has(&key) {
+            return Err(VoteError::AlreadyFinalized);
+        }
+        
+        // Same preconditions as get_winner (poll over, results visible)
+        let outcome = Self::get_winner(env.clone(), proposal_id)?;
+        
+        let result = PollResult {
+            proposal_id,
+            tally: Self::read_tally(&env, proposal_id),
+            total_votes: Self::read_total_votes(&env, proposal_id),
+            ballot_count: Self::get_ballot_count(env.clone(), proposal_id),
+            outcome,
+            ledger_sequence: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&key, &result);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+        
+        if !proposal.closed {
+            proposal.closed = true;
+            env.storage().persistent().set(&DataKey::Proposal(proposal_id), &proposal);
+        }
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        PollFinalized { proposal_id, result: result.clone() }.publish(&env);
+        
+        Ok(result)
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     MAIN VOTING FUNCTION
+    // ─────────────────────────────────────────────────────────────────────────
+    pub fn vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        choice: String,
+        vote_count: u64,
+    ) -> Result<(), VoteError> {
+        // ═══════════════════════════════════════════════════════════════════
+        //              AUTHENTICATION
+        // ═══════════════════════════════════════════════════════════════════
+        voter.require_auth();
+        
+        // ═══════════════════════════════════════════════════════════════════
+        //                     VALIDATION CHECKS
+        // ═══════════════════════════════════════════════════════════════════
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        
+        Self::require_open_ballot(&proposal)?;
+        Self::require_single_choice(&proposal)?;
+        Self::require_eligible(&env, &proposal, &voter, &Vec::new(&env))?;
+        
+        Self::cast_ballot(&env, &proposal, voter, choice, vote_count, Vec::new(&env), Map::new(&env))
+    }
+    
+    /// `vote` for MerkleRoot polls: `proof` shows the voter is in the tree.
+    /// In any other poll the proof is ignored.
+    pub fn vote_with_proof(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        choice: String,
+        vote_count: u64,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        Self::require_open_ballot(&proposal)?;
+        Self::require_single_choice(&proposal)?;
+        Self::require_eligible(&env, &proposal, &voter, &proof)?;
+        
+        Self::cast_ballot(&env, &proposal, voter, choice, vote_count, Vec::new(&env), Map::new(&env))
+    }
+    
+    /// Cast a ranked ballot in a RankedChoice poll: every listed option in
+    /// order of preference (at least one, no repeats). Counts as one vote.
+    pub fn vote_ranked(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        ranking: Vec<String>,
+    ) -> Result<(), VoteError> {
+        let proof = Vec::new(&env);
+        Self::vote_ranked_with_proof(env, voter, proposal_id, ranking, proof)
+    }
+    
+    /// `vote_ranked` for MerkleRoot polls
+    pub fn vote_ranked_with_proof(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        ranking: Vec<String>,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        if proposal.config.mode != VotingMode::RankedChoice {
+            return Err(VoteError::NotRankedChoice);
+        }
+        Self::require_eligible(&env, &proposal, &voter, &proof)?;
+        
+        let mut seen: Map<String, bool> = Map::new(&env);
+        for option in ranking.iter() {
+            Self::require_option(&env, proposal_id, &option)?;
+            if seen.contains_key(option.clone()) {
+                return Err(VoteError::InvalidRanking);
+            }
+            seen.set(option, true);
+        }
+        let first = ranking.first().ok_or(VoteError::InvalidRanking)?;
+        
+        Self::cast_ballot(&env, &proposal, voter, first, 1, ranking, Map::new(&env))
+    }
+    
+    /// Cast one ballot in a Quadratic poll split over several options.
+    /// Every listed option needs at least one vote, and the summed cost
+    /// Σ votes² must fit the budget. The option with the most votes is
+    /// recorded as the ballot's `choice`.
+    pub fn vote_quadratic(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        allocation: Map<String, u64>,
+    ) -> Result<(), VoteError> {
+        let proof = Vec::new(&env);
+        Self::vote_quadratic_with_proof(env, voter, proposal_id, allocation, proof)
+    }
+    
+    /// `vote_quadratic` for MerkleRoot polls
+    pub fn vote_quadratic_with_proof(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        allocation: Map<String, u64>,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        Self::require_open_ballot(&proposal)?;
+        if !matches!(proposal.config.mode, VotingMode::Quadratic(_)) {
+            return Err(VoteError::NotQuadratic);
+        }
+        Self::require_eligible(&env, &proposal, &voter, &proof)?;
+        
+        let mut choice: Option<String> = None;
+        let mut most: u64 = 0;
+        let mut total: u64 = 0;
+        for (option, votes) in allocation.iter() {
+            Self::require_option(&env, proposal_id, &option)?;
+            if votes > most {
+                most = votes;
+                choice = Some(option);
+            }
+            total = total.checked_add(votes).ok_or(VoteError::Overflow)?;
+        }
+        // Empty, or nothing but zeros
+        let choice = choice.ok_or(VoteError::TooFewVotes)?;
+        
+        Self::cast_ballot(&env, &proposal, voter, choice, total, Vec::new(&env), allocation)
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     COMMIT–REVEAL (SECRET BALLOTS)
+    // ─────────────────────────────────────────────────────────────────────────
+    // In a poll with `reveal_end_time` set, voting happens in two phases:
+    //
+    //   start_time ≤ now < end_time        commit_vote: store sha256(choice ‖ vote_count ‖ salt)
+    //   end_time   ≤ now < reveal_end_time reveal_vote: show all three, get counted
+    //
+    // The weight is part of the commitment, so nobody can size their vote
+    // after seeing earlier reveals. Nothing is tallied during the commit
+    // phase, and the results and ballot views stay hidden until the reveal
+    // phase is over. Eligibility is checked when the commitment is stored,
+    // so reveals need no proof.
+    
+    /// Store the hash of a ballot. Committing again replaces the earlier hash.
+    pub fn commit_vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        commitment: BytesN<32>,
+    ) -> Result<(), VoteError> {
+        let proof = Vec::new(&env);
+        Self::commit_vote_with_proof(env, voter, proposal_id, commitment, proof)
+    }
+    
+    /// `commit_vote` for MerkleRoot polls
+    pub fn commit_vote_with_proof(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        commitment: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        if proposal.config.reveal_end_time.is_none() {
+            return Err(VoteError::NotSecretBallot);
+        }
+        Self::require_eligible(&env, &proposal, &voter, &proof)?;
+        
+        let key = DataKey::Commitment(proposal_id, voter.clone());
+        if !env.storage().persistent().has(&key) {
+            let mut committers = Self::read_committers(&env, proposal_id);
+            committers.push_back(voter.clone());
+            Self::write_persistent(&env, &DataKey::Committers(proposal_id), &committers);
+        }
+        Self::write_persistent(&env, &key, &commitment);
+        
+        VoteCommitted { proposal_id, voter, commitment }.publish(&env);
+        
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        Ok(())
+    }
+    
+    /// Open a commitment during the reveal phase. The ballot is counted
+    /// exactly like `vote`, as long as sha256(choice ‖ vote_count ‖ salt) matches.
+    pub fn reveal_vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        choice: String,
+        vote_count: u64,
+        salt: BytesN<32>,
+    ) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        let reveal_end_time = proposal
+            .config
+            .reveal_end_time
+            .ok_or(VoteError::NotSecretBallot)?;
+        
+        let now = env.ledger().timestamp();
+        if now < proposal.config.end_time || now >= reveal_end_time {
+            return Err(VoteError::NotRevealPhase);
+        }
+        
+        let commitment: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(proposal_id, voter.clone()))
+            .ok_or(VoteError::CommitmentNotFound)?;
+        if Self::commitment_hash(env.clone(), choice.clone(), vote_count, salt) != commitment {
+            return Err(VoteError::CommitmentMismatch);
+        }
+        
+        Self::cast_ballot(&env, &proposal, voter, choice, vote_count, Vec::new(&env), Map::new(&env))
+    }
+    
+    /// The hash `commit_vote` expects: sha256 of the choice's UTF-8 bytes,
+    /// the vote count as 8 big-endian bytes and the 32 salt bytes.
+    /// Handy for clients and tests.
+    pub fn commitment_hash(env: Env, choice: String, vote_count: u64, salt: BytesN<32>) -> BytesN<32> {
+        let mut preimage = choice.to_bytes();
+        preimage.append(&Bytes::from_array(&env, &vote_count.to_be_bytes()));
+        preimage.append(&Bytes::from(salt));
+        env.crypto().sha256(&preimage).into()
+    }
+    
+    /// Voters who committed but have not (yet) revealed
+    pub fn get_unrevealed(env: Env, proposal_id: u32) -> Result<Vec<Address>, VoteError> {
+        Self::load_proposal(&env, proposal_id)?;
+        
+        let mut unrevealed: Vec<Address> = Vec::new(&env);
+        for committer in Self::read_committers(&env, proposal_id).iter() {
+            if !Self::has_voted(env.clone(), proposal_id, committer.clone()) {
+                unrevealed.push_back(committer);
+            }
+        }
+        Ok(unrevealed)
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     CHANGE / RETRACT
+    // ─────────────────────────────────────────────────────────────────────────
+    
+    /// Move an existing ballot to another option while the poll is open.
+    /// The weight (and credits spent) stay exactly as originally cast.
+    pub fn change_vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        new_choice: String,
+    ) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        Self::require_open_ballot(&proposal)?;
+        Self::require_single_choice(&proposal)?;
+        Self::require_option(&env, proposal_id, &new_choice)?;
+        
+        let record = Self::read_voter(&env, proposal_id, voter.clone())
+            .ok_or(VoteError::VoterNotFound)?;
+        if record.cast_by != voter {
+            return Err(VoteError::CastByDelegate);
+        }
+        if !record.allocation.is_empty() {
+            return Err(VoteError::SplitBallot);
+        }
+        
+        // The voter's own ballot plus every ballot they cast as a delegate
+        let mut ballots: Vec<VoterRecord> = Vec::new(&env);
+        ballots.push_back(record);
+        ballots.append(&Self::ballots_cast_by(&env, proposal_id, &voter, &voter));
+        
+        let now = env.ledger().timestamp();
+        for mut ballot in ballots.iter() {
+            // Take the weight off the old option and put it on the new one
+            Self::remove_votes(&env, proposal_id, &ballot.choice, ballot.votes)?;
+            Self::add_votes(&env, proposal_id, &new_choice, ballot.votes)?;
+            
+            let previous_choice = ballot.choice.clone();
+            ballot.choice = new_choice.clone();
+            ballot.timestamp = now;
+            Self::write_voter_record(&env, &mut ballot);
+            
+            VoteChanged {
+                proposal_id,
+                voter: ballot.voter.clone(),
+                change: VoteChange { previous_choice, record: ballot },
+            }
+            .publish(&env);
+        }
+        
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        Ok(())
+    }
+    
+    /// Withdraw a ballot while the poll is open. The voter may vote again later.
+    /// Ballots the voter cast on behalf of their delegators are withdrawn too.
+    pub fn retract_vote(env: Env, voter: Address, proposal_id: u32) -> Result<(), VoteError> {
+        voter.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        Self::require_open_ballot(&proposal)?;
+        
+        let record = Self::read_voter(&env, proposal_id, voter.clone())
+            .ok_or(VoteError::VoterNotFound)?;
+        if record.cast_by != voter {
+            return Err(VoteError::CastByDelegate);
+        }
+        
+        Self::remove_ballot(&env, &record)?;
+        for ballot in Self::ballots_cast_by(&env, proposal_id, &voter, &voter).iter() {
+            Self::remove_ballot(&env, &ballot)?;
+        }
+        Self::release_tokens(&env, &proposal, &voter);
+        
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        Ok(())
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     DELEGATION
+    // ─────────────────────────────────────────────────────────────────────────
+    // Delegation is per poll. Chains are followed transitively (a → b → c means
+    // c's ballot is cast for a and b too), but may not loop back on themselves
+    // or be longer than MAX_DELEGATION_DEPTH links. A vote writes a ballot for
+    // every delegator below the voter, so no tree may hold more than
+    // MAX_DELEGATORS of them; otherwise piling throwaway delegations onto an
+    // address could push its vote past the ledger limits.
+    
+    /// Hand `from`'s vote in this poll to `to`.
+    /// In token-weighted polls this locks `from`'s whole balance.
+    pub fn delegate(env: Env, from: Address, proposal_id: u32, to: Address) -> Result<(), VoteError> {
+        from.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        Self::require_open_ballot(&proposal)?;
+        Self::require_eligible(&env, &proposal, &from, &Vec::new(&env))?;
+        
+        if Self::has_voted(env.clone(), proposal_id, from.clone()) {
+            return Err(VoteError::AlreadyVoted);
+        }
+        if Self::get_delegate(env.clone(), proposal_id, from.clone()).is_some() {
+            return Err(VoteError::AlreadyDelegated);
+        }
+        if Self::has_voted(env.clone(), proposal_id, to.clone()) {
+            return Err(VoteError::DelegateAlreadyVoted);
+        }
+        
+        // Walk up from `to`: reaching `from` again would be a cycle
+        let mut links_above: u32 = 0;
+        let mut current = to.clone();
+        loop {
+            if current == from {
+                return Err(VoteError::DelegationCycle);
+            }
+            match Self::get_delegate(env.clone(), proposal_id, current) {
+                Some(next) => {
+                    links_above += 1;
+                    if links_above >= MAX_DELEGATION_DEPTH {
+                        return Err(VoteError::DelegationTooDeep);
+                    }
+                    current = next;
+                }
+                None => break,
+            }
+        }
+        
+        // Longest chain through the new link: below `from` + this link + above `to`
+        let links_below = Self::delegation_height(&env, proposal_id, &from);
+        if links_below + 1 + links_above > MAX_DELEGATION_DEPTH {
+            return Err(VoteError::DelegationTooDeep);
+        }
+        
+        // The top of the chain casts for everyone below it, so its tree is the one to cap
+        let mut root = to.clone();
+        while let Some(next) = Self::get_delegate(env.clone(), proposal_id, root.clone()) {
+            root = next;
+        }
+        let joining = Self::collect_delegators(&env, proposal_id, &from).len() + 1;
+        if Self::collect_delegators(&env, proposal_id, &root).len() + joining > MAX_DELEGATORS {
+            return Err(VoteError::TooManyDelegators);
+        }
+        
+        if let VotingMode::TokenWeighted(token_id) = &proposal.config.mode {
+            let balance = token::TokenClient::new(&env, token_id).balance(&from);
+            u64::try_from(balance).map_err(|_| VoteError::Overflow)?;
+            if balance > 0 {
+                Self::lock_tokens(&env, token_id, proposal_id, &from, balance);
+            }
+        }
+        
+        Self::write_persistent(&env, &DataKey::Delegation(proposal_id, from.clone()), &to);
+        
+        let mut delegators = Self::get_delegators(env.clone(), proposal_id, to.clone());
+        delegators.push_back(from);
+        Self::write_persistent(&env, &DataKey::Delegators(proposal_id, to), &delegators);
+        
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        Ok(())
+    }
+    
+    /// Take back a delegation. If the delegate already voted for `from`, that
+    /// ballot (and the ones it covered further down the chain) is withdrawn
+    /// so `from` can vote or delegate again.
+    pub fn undelegate(env: Env, from: Address, proposal_id: u32) -> Result<(), VoteError> {
+        from.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_open(&env, &proposal)?;
+        
+        let to = Self::get_delegate(env.clone(), proposal_id, from.clone())
+            .ok_or(VoteError::NotDelegated)?;
+        
+        if let Some(record) = Self::read_voter(&env, proposal_id, from.clone()) {
+            Self::remove_ballot(&env, &record)?;
+            for ballot in Self::ballots_cast_by(&env, proposal_id, &from, &record.cast_by).iter() {
+                Self::remove_ballot(&env, &ballot)?;
+            }
+        }
+        
+        env.storage().persistent().remove(&DataKey::Delegation(proposal_id, from.clone()));
+        Self::release_tokens(&env, &proposal, &from);
+        
+        let mut delegators = Self::get_delegators(env.clone(), proposal_id, to.clone());
+        if let Some(index) = delegators.first_index_of(&from) {
+            delegators.remove(index);
+        }
+        Self::write_persistent(&env, &DataKey::Delegators(proposal_id, to), &delegators);
+        
+        Self::extend_proposal_ttl(&env, proposal_id);
+        
+        Ok(())
+    }
+    
+    pub fn get_delegate(env: Env, proposal_id: u32, from: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Delegation(proposal_id, from))
+    }
+    
+    /// Direct delegators only; see `collect_delegators` for the whole tree
+    pub fn get_delegators(env: Env, proposal_id: u32, to: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Delegators(proposal_id, to))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     TOKEN LOCKS
+    // ─────────────────────────────────────────────────────────────────────────
+    // Only token-weighted polls lock anything. Tokens stay locked while they
+    // back a ballot, so they cannot be withdrawn before voting (and any reveal
+    // phase) is over.
+    
+    /// Send back the tokens this poll holds for `holder`; returns the amount
+    pub fn withdraw_tokens(env: Env, holder: Address, proposal_id: u32) -> Result<i128, VoteError> {
+        holder.require_auth();
+        
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        let voting_end = proposal.config.reveal_end_time.unwrap_or(proposal.config.end_time);
+        if !proposal.closed && env.ledger().timestamp() < voting_end {
+            return Err(VoteError::PollNotClosed);
+        }
+        
+        match Self::release_tokens(&env, &proposal, &holder) {
+            0 => Err(VoteError::NothingLocked),
+            amount => Ok(amount),
+        }
+    }
+    
+    /// Tokens the contract currently holds for `holder` in this poll
+    pub fn get_locked(env: Env, proposal_id: u32, holder: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Locked(proposal_id, holder))
+            .unwrap_or(0)
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     VIEW FUNCTIONS
+    // ─────────────────────────────────────────────────────────────────────────
+    
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Result<Proposal, VoteError> {
+        Self::load_proposal(&env, proposal_id)
+    }
+    
+    /// Whether `vote` would currently accept ballots for this poll
+    pub fn is_open(env: Env, proposal_id: u32) -> Result<bool, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Ok(Self::require_open(&env, &proposal).is_ok())
+    }
+    
+    pub fn get_proposal_count(env: Env) -> u32 {
+        env.storage().instance().get(&PROPOSAL_COUNT).unwrap_or(0)
+    }
+    
+    /// Votes received by one option (0 for unknown options)
+    pub fn get_option_votes(env: Env, proposal_id: u32, option: String) -> Result<u64, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_results_visible(&env, &proposal)?;
+        Ok(Self::read_tally(&env, proposal_id).get(option).unwrap_or(0))
+    }
+    
+    pub fn get_total_votes(env: Env, proposal_id: u32) -> Result<u64, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_results_visible(&env, &proposal)?;
+        Ok(Self::read_total_votes(&env, proposal_id))
+    }
+    
+    /// A voter's ballot; hidden in a secret poll until the reveal phase ends
+    pub fn get_voter(env: Env, proposal_id: u32, voter: Address) -> Result<Option<VoterRecord>, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_results_visible(&env, &proposal)?;
+        Ok(Self::read_voter(&env, proposal_id, voter))
+    }
+    
+    pub fn has_voted(env: Env, proposal_id: u32, voter: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Voter(proposal_id, voter))
+    }
+    
+    /// Live tally: votes per option
+    pub fn get_results(env: Env, proposal_id: u32) -> Result<Map<String, u64>, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_results_visible(&env, &proposal)?;
+        Ok(Self::read_tally(&env, proposal_id))
+    }
+    
+    /// Live tally sorted by votes (highest first); tied options share a rank
+    pub fn get_ranked_results(env: Env, proposal_id: u32) -> Result<Vec<RankedOption>, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_results_visible(&env, &proposal)?;
+        let tally = Self::read_tally(&env, proposal_id);
+        
+        // Insertion sort by votes, descending. Options are capped at
+        // MAX_OPTIONS, and equal counts keep the ProposalConfig order.
+        let mut ranked: Vec<RankedOption> = Vec::new(&env);
+        for option in proposal.config.options.iter() {
+            let votes = tally.get(option.clone()).unwrap_or(0);
+            let mut pos = ranked.len();
+            while pos > 0 && ranked.get_unchecked(pos - 1).votes < votes {
+                pos -= 1;
+            }
+            ranked.insert(pos, RankedOption { rank: 0, option, votes });
+        }
+        
+        // Assign "1, 1, 3" style ranks
+        for i in 0..ranked.len() {
+            let mut row = ranked.get_unchecked(i);
+            row.rank = if i > 0 && ranked.get_unchecked(i - 1).votes == row.votes {
+                ranked.get_unchecked(i - 1).rank
+            } else {
+                i + 1
+            };
+            ranked.set(i, row);
+        }
+        
+        Ok(ranked)
+    }
+    
+    /// Final result, only available once the poll has closed.
+    /// Quorum is checked first, then ties, then the threshold.
+    pub fn get_winner(env: Env, proposal_id: u32) -> Result<PollOutcome, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        if !proposal.closed && env.ledger().timestamp() < proposal.config.end_time {
+            return Err(VoteError::PollNotClosed);
+        }
+        Self::require_results_visible(&env, &proposal)?;
+        
+        if !Self::quorum_met(&env, &proposal) {
+            return Ok(PollOutcome::QuorumNotMet);
+        }
+        
+        // The runoff already requires a majority, so the threshold is not applied
+        if proposal.config.mode == VotingMode::RankedChoice {
+            let report = Self::run_runoff(&env, &proposal);
+            return Ok(match report.winner {
+                Some(winner) => PollOutcome::Passed(winner),
+                None => {
+                    let last = report.rounds.last().unwrap();
+                    PollOutcome::Tie(last.counts.keys())
+                }
+            });
+        }
+        
+        let mut leaders: Vec<String> = Vec::new(&env);
+        let mut top_votes: u64 = 0;
+        for row in Self::get_ranked_results(env.clone(), proposal_id)?.iter() {
+            if row.rank == 1 {
+                leaders.push_back(row.option);
+                top_votes = row.votes;
+            }
+        }
+        if leaders.len() > 1 {
+            return Ok(PollOutcome::Tie(leaders));
+        }
+        
+        // u128 so that token-weighted totals cannot overflow the comparison
+        let top = top_votes as u128;
+        let total = Self::read_total_votes(&env, proposal_id) as u128;
+        let passed = match proposal.config.threshold {
+            Threshold::Plurality => true,
+            Threshold::SimpleMajority => top * 2 > total,
+            Threshold::TwoThirds => top * 3 >= total * 2,
+            Threshold::Unanimity => top == total,
+        };
+        
+        let leader = leaders.get(0).unwrap();
+        if passed {
+            Ok(PollOutcome::Passed(leader))
+        } else {
+            Ok(PollOutcome::ThresholdNotMet(leader))
+        }
+    }
+    
+    /// Round-by-round instant runoff for a RankedChoice poll. Like get_results
+    /// it can be called while voting is still open.
+    pub fn get_runoff(env: Env, proposal_id: u32) -> Result<RunoffReport, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        if proposal.config.mode != VotingMode::RankedChoice {
+            return Err(VoteError::NotRankedChoice);
+        }
+        Ok(Self::run_runoff(&env, &proposal))
+    }
+    
+    /// The snapshot written by finalize
+    pub fn get_poll_result(env: Env, proposal_id: u32) -> Result<PollResult, VoteError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Result(proposal_id))
+            .ok_or(VoteError::NotFinalized)
+    }
+    
+    /// Page through everyone who ever voted in the poll, in first-vote order.
+    /// The index is append-only: a retracted ballot leaves its position empty
+    /// (and is skipped here), and voting again reuses the old position, so
+    /// `start` stays stable between calls. Pages hold at most MAX_PAGE_SIZE
+    /// positions. Summing over all pages gives get_results: a record with an
+    /// empty `allocation` adds its `votes` to `choice`, a split quadratic
+    /// ballot adds each entry of `allocation` to its own option instead.
+    /// Secret polls keep the list hidden until the reveal phase ends.
+    pub fn list_voters(
+        env: Env,
+        proposal_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<VoterRecord>, VoteError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Self::require_results_visible(&env, &proposal)?;
+        
+        let len = Self::get_voter_index_len(env.clone(), proposal_id);
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+        
+        let mut records: Vec<VoterRecord> = Vec::new(&env);
+        for position in start..end {
+            // An index entry that is gone (archived) is skipped like a retracted ballot
+            let Some(voter) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Address>(&DataKey::VoterAt(proposal_id, position))
+            else {
+                continue;
+            };
+            if let Some(record) = Self::read_voter(&env, proposal_id, voter) {
+                records.push_back(record);
+            }
+        }
+        Ok(records)
+    }
+    
+    /// Length of the voter index, i.e. the end of the last page
+    pub fn get_voter_index_len(env: Env, proposal_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VoterIndexLen(proposal_id))
+            .unwrap_or(0)
+    }
+    
+    /// Ballots cast so far, delegated ones included
+    pub fn get_ballot_count(env: Env, proposal_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BallotCount(proposal_id))
+            .unwrap_or(0)
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     TTL MANAGEMENT FUNCTIONS
+    // ─────────────────────────────────────────────────────────────────────────
+    
+    /// Extend the contract instance TTL (anyone can call this)
+    /// Useful to keep the contract alive if no one is voting
+    pub fn extend_contract_ttl(env: Env) {
+        env.storage().instance().extend_ttl(
+            INSTANCE_TTL_THRESHOLD,
+            INSTANCE_TTL
+        );
+        
+        TtlExtended {
+            extension: TtlExtension { proposal_id: None, voter: None, extend_to: INSTANCE_TTL },
+        }
+        .publish(&env);
+    }
+    
+    /// Keep a poll's shared entries alive: the proposal, its tallies, the
+    /// ranked ballots and committers, and the finalized PollResult (anyone
+    /// can call this). Voter records are extended with extend_voters_ttl.
+    pub fn extend_poll_ttl(env: Env, proposal_id: u32) -> Result<(), VoteError> {
+        Self::load_proposal(&env, proposal_id)?;
+        
+        for key in [
+            DataKey::Proposal(proposal_id),
+            DataKey::Tally(proposal_id),
+            DataKey::TotalVotes(proposal_id),
+            DataKey::BallotCount(proposal_id),
+            DataKey::Rankings(proposal_id),
+            DataKey::Committers(proposal_id),
+            DataKey::Result(proposal_id),
+        ] {
+            // Entries the poll never wrote (no ballots yet, not ranked, ...) are skipped
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+            }
+        }
+        env.storage().instance().extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL);
+        
+        TtlExtended {
+            extension: TtlExtension { proposal_id: Some(proposal_id), voter: None, extend_to: PROPOSAL_TTL },
+        }
+        .publish(&env);
+        
+        Ok(())
+    }
+    
+    /// Extend a specific voter's record TTL
+    /// The voter can call this to keep their vote record alive
+    pub fn extend_voter_ttl(env: Env, proposal_id: u32, voter: Address) -> Result<(), VoteError> {
+        // Only the voter can extend their own record
+        voter.require_auth();
+        
+        if !Self::has_voted(env.clone(), proposal_id, voter.clone()) {
+            return Err(VoteError::VoterNotFound);
+        }
+        
+        Self::extend_voter_record(&env, proposal_id, &voter);
+        
+        Ok(())
+    }
+    
+    /// Extend a batch of voter records on someone else's dime.
+    /// Anyone can call this (no auth), e.g. an auditor keeping the trail alive.
+    /// Addresses without a record are skipped; returns how many were extended.
+    pub fn extend_voters_ttl(env: Env, proposal_id: u32, voters: Vec<Address>) -> Result<u32, VoteError> {
+        Self::load_proposal(&env, proposal_id)?;
+        
+        let mut extended: u32 = 0;
+        for voter in voters.iter() {
+            if Self::has_voted(env.clone(), proposal_id, voter.clone()) {
+                Self::extend_voter_record(&env, proposal_id, &voter);
+                extended += 1;
+            }
+        }
+        Ok(extended)
+    }
+    
+    /// Remaining TTL of each listed voter's record, flagging the ones that
+    /// will be archived within a month unless someone extends them.
+    /// Addresses without a record are left out.
+    pub fn get_voter_ttls(env: Env, proposal_id: u32, voters: Vec<Address>) -> Vec<RecordTtl> {
+        let now = env.ledger().sequence();
+        
+        let mut ttls: Vec<RecordTtl> = Vec::new(&env);
+        for voter in voters.iter() {
+            if let Some(record) = Self::read_voter(&env, proposal_id, voter.clone()) {
+                let live_until_ledger = record.live_until_ledger;
+                let ttl = live_until_ledger.saturating_sub(now);
+                ttls.push_back(RecordTtl {
+                    voter,
+                    live_until_ledger,
+                    ttl,
+                    near_archival: ttl < VOTER_RECORD_TTL_THRESHOLD,
+                });
+            }
+        }
+        ttls
+    }
+    
+    // ─────────────────────────────────────────────────────────────────────────
+    //                     INTERNAL HELPERS
+    // ─────────────────────────────────────────────────────────────────────────
+    
+    /// Validate and record one ballot (plus ballots for the voter's delegators).
+    /// Shared by `vote`, `vote_ranked`, `vote_quadratic` and `reveal_vote`;
+    /// callers check auth, the phase and eligibility. `ranking` is empty unless
+    /// the poll is ranked, `allocation` unless the ballot is a quadratic split.
+    fn cast_ballot(
+        env: &Env,
+        proposal: &Proposal,
+        voter: Address,
+        choice: String,
+        vote_count: u64,
+        ranking: Vec<String>,
+        allocation: Map<String, u64>,
+    ) -> Result<(), VoteError> {
+        let proposal_id = proposal.id;
+        
+        let credits_spent = if allocation.is_empty() {
+            Self::vote_cost(env, &proposal.config.mode, &voter, vote_count)?
+        } else {
+            Self::split_cost(&proposal.config.mode, &allocation)?
+        };
+        
+        Self::require_option(env, proposal_id, &choice)?;
+        
+        if Self::has_voted(env.clone(), proposal_id, voter.clone()) {
+            return Err(VoteError::AlreadyVoted);
+        }
+        if Self::get_delegate(env.clone(), proposal_id, voter.clone()).is_some() {
+            return Err(VoteError::AlreadyDelegated);
+        }
+        
+        if let VotingMode::TokenWeighted(token_id) = &proposal.config.mode {
+            Self::lock_tokens(env, token_id, proposal_id, &voter, i128::from(vote_count));
+        }
+        
+        // ═══════════════════════════════════════════════════════════════════
+        //                     RECORD THE VOTE
+        // ═══════════════════════════════════════════════════════════════════
+        let mut record = VoterRecord {
+            proposal_id,
+            voter: voter.clone(),
+            choice: choice.clone(),
+            votes: vote_count,
+            credits_spent,
+            timestamp: env.ledger().timestamp(),
+            cast_by: voter.clone(),
+            ranking: ranking.clone(),
+            allocation: allocation.clone(),
+            live_until_ledger: 0, // Set by write_voter_record
+        };
+        
+        // Save voter record (also extends its TTL)
+        Self::write_voter_record(env, &mut record);
+        VoteCast { proposal_id, voter: voter.clone(), record: record.clone() }.publish(env);
+        
+        // ═══════════════════════════════════════════════════════════════════
+        //                     UPDATE TOTALS
+        // ═══════════════════════════════════════════════════════════════════
+        Self::add_ballot_votes(env, &record)?;
+        Self::add_ranking(env, proposal, &record)?;
+        Self::add_ballot_count(env, proposal_id, 1);
+        
+        // ═══════════════════════════════════════════════════════════════════
+        //                     CAST FOR DELEGATORS
+        // ═══════════════════════════════════════════════════════════════════
+        // Everyone who (transitively) delegated to this voter gets the same
+        // ballot, weighted by their own voting power, with cast_by = voter.
+        // A quadratic split is copied as is. Delegators revoked since they
+        // delegated are skipped.
+        for delegator in Self::collect_delegators(env, proposal_id, &voter).iter() {
+            if Self::require_eligible(env, proposal, &delegator, &Vec::new(env)).is_err() {
+                continue;
+            }
+            let (votes, credits_spent) = if allocation.is_empty() {
+                Self::delegated_weight(env, proposal, &delegator, vote_count)?
+            } else {
+                (vote_count, credits_spent)
+            };
+            if votes == 0 {
+                continue;
+            }
+            
+            let mut delegated = VoterRecord {
+                proposal_id,
+                voter: delegator.clone(),
+                choice: choice.clone(),
+                votes,
+                credits_spent,
+                timestamp: record.timestamp,
+                cast_by: voter.clone(),
+                ranking: ranking.clone(),
+                allocation: allocation.clone(),
+                live_until_ledger: 0,
+            };
+            Self::write_voter_record(env, &mut delegated);
+            Self::add_ballot_votes(env, &delegated)?;
+            Self::add_ranking(env, proposal, &delegated)?;
+            Self::add_ballot_count(env, proposal_id, 1);
+            VoteCast { proposal_id, voter: delegator, record: delegated }.publish(env);
+        }
+        
+        // Every time someone votes, extend the poll's and the contract's life
+        Self::extend_proposal_ttl(env, proposal_id);
+        
+        Ok(())
+    }
+    
+    fn load_proposal(env: &Env, proposal_id: u32) -> Result<Proposal, VoteError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .ok_or(VoteError::ProposalNotFound)
+    }
+    
+    /// Validates vote_count against the poll's mode and returns the credits it costs
+    fn vote_cost(
+        env: &Env,
+        mode: &VotingMode,
+        voter: &Address,
+        vote_count: u64,
+    ) -> Result<u64, VoteError> {
+        if vote_count < 1 {
+            return Err(VoteError::TooFewVotes);
+        }
+        
+        match mode {
+            VotingMode::Standard => {
+                if vote_count > MAX_STANDARD_VOTES {
+                    return Err(VoteError::TooManyVotes);
+                }
+                Ok(0)
+            }
+            VotingMode::RankedChoice => {
+                if vote_count > 1 {
+                    return Err(VoteError::TooManyVotes);
+                }
+                Ok(0)
+            }
+            VotingMode::Quadratic(budget) => {
+                let cost = vote_count
+                    .checked_mul(vote_count)
+                    .ok_or(VoteError::InsufficientCredits)?;
+                if cost > *budget {
+                    return Err(VoteError::InsufficientCredits);
+                }
+                Ok(cost)
+            }
+            VotingMode::TokenWeighted(token_id) => {
+                // Cross-contract call into the SEP-41 token
+                let balance = token::TokenClient::new(env, token_id).balance(voter);
+                if i128::from(vote_count) > balance {
+                    return Err(VoteError::InsufficientVotingPower);
+                }
+                Ok(0)
+            }
+        }
+    }
+    
+    /// Credits a quadratic split costs: Σ votes² over its options
+    fn split_cost(mode: &VotingMode, allocation: &Map<String, u64>) -> Result<u64, VoteError> {
+        let VotingMode::Quadratic(budget) = mode else {
+            return Err(VoteError::NotQuadratic);
+        };
+        
+        let mut cost: u64 = 0;
+        for (_, votes) in allocation.iter() {
+            if votes < 1 {
+                return Err(VoteError::TooFewVotes);
+            }
+            let square = votes.checked_mul(votes).ok_or(VoteError::InsufficientCredits)?;
+            cost = cost.checked_add(square).ok_or(VoteError::InsufficientCredits)?;
+        }
+        if cost > *budget {
+            return Err(VoteError::InsufficientCredits);
+        }
+        Ok(cost)
+    }
+    
+    /// Weight a delegate's ballot carries for one delegator: the same vote
+    /// count (and credits) in standard/quadratic polls, or the tokens the
+    /// delegator locked when delegating in token-weighted polls.
+    fn delegated_weight(
+        env: &Env,
+        proposal: &Proposal,
+        delegator: &Address,
+        vote_count: u64,
+    ) -> Result<(u64, u64), VoteError> {
+        match proposal.config.mode {
+            VotingMode::Standard | VotingMode::RankedChoice => Ok((vote_count, 0)),
+            VotingMode::Quadratic(_) => Ok((vote_count, vote_count * vote_count)),
+            VotingMode::TokenWeighted(_) => {
+                let locked = Self::get_locked(env.clone(), proposal.id, delegator.clone());
+                let votes = u64::try_from(locked).map_err(|_| VoteError::Overflow)?;
+                Ok((votes, 0))
+            }
+        }
+    }
+    
+    /// Move `amount` of the poll's token from `holder` into the contract
+    fn lock_tokens(env: &Env, token_id: &Address, proposal_id: u32, holder: &Address, amount: i128) {
+        token::TokenClient::new(env, token_id).transfer(holder, env.current_contract_address(), &amount);
+        Self::write_persistent(env, &DataKey::Locked(proposal_id, holder.clone()), &amount);
+    }
+    
+    /// Send back whatever the poll holds for `holder`; returns the amount
+    fn release_tokens(env: &Env, proposal: &Proposal, holder: &Address) -> i128 {
+        let VotingMode::TokenWeighted(token_id) = &proposal.config.mode else {
+            return 0;
+        };
+        
+        let locked = Self::get_locked(env.clone(), proposal.id, holder.clone());
+        if locked > 0 {
+            token::TokenClient::new(env, token_id).transfer(&env.current_contract_address(), holder, &locked);
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Locked(proposal.id, holder.clone()));
+        }
+        locked
+    }
+    
+    /// Every address that delegates to `root`, directly or through a chain.
+    /// Bounded by MAX_DELEGATION_DEPTH levels and MAX_DELEGATORS addresses,
+    /// which `delegate` enforces.
+    fn collect_delegators(env: &Env, proposal_id: u32, root: &Address) -> Vec<Address> {
+        let mut all: Vec<Address> = Vec::new(env);
+        let mut level = Self::get_delegators(env.clone(), proposal_id, root.clone());
+        let mut depth = 0;
+        
+        while !level.is_empty() && depth < MAX_DELEGATION_DEPTH {
+            let mut next_level: Vec<Address> = Vec::new(env);
+            for delegator in level.iter() {
+                next_level.append(&Self::get_delegators(env.clone(), proposal_id, delegator.clone()));
+                all.push_back(delegator);
+            }
+            level = next_level;
+            depth += 1;
+        }
+        
+        all
+    }
+    
+    /// Number of delegation links in the longest chain ending at `root`
+    fn delegation_height(env: &Env, proposal_id: u32, root: &Address) -> u32 {
+        let mut level = Self::get_delegators(env.clone(), proposal_id, root.clone());
+        let mut height = 0;
+        
+        while !level.is_empty() && height <= MAX_DELEGATION_DEPTH {
+            let mut next_level: Vec<Address> = Vec::new(env);
+            for delegator in level.iter() {
+                next_level.append(&Self::get_delegators(env.clone(), proposal_id, delegator));
+            }
+            level = next_level;
+            height += 1;
+        }
+        
+        height
+    }
+    
+    /// Ballots below `root` in the delegation tree that were cast by `caster`
+    fn ballots_cast_by(
+        env: &Env,
+        proposal_id: u32,
+        root: &Address,
+        caster: &Address,
+    ) -> Vec<VoterRecord> {
+        let mut ballots: Vec<VoterRecord> = Vec::new(env);
+        for delegator in Self::collect_delegators(env, proposal_id, root).iter() {
+            if let Some(record) = Self::read_voter(env, proposal_id, delegator) {
+                if record.cast_by == *caster {
+                    ballots.push_back(record);
+                }
+            }
+        }
+        ballots
+    }
+    
+    fn remove_ballot(env: &Env, record: &VoterRecord) -> Result<(), VoteError> {
+        for (option, votes) in Self::ballot_split(env, record).iter() {
+            Self::remove_votes(env, record.proposal_id, &option, votes)?;
+        }
+        if !record.ranking.is_empty() {
+            Self::remove_ranking(env, &Self::load_proposal(env, record.proposal_id)?, record)?;
+        }
+        Self::add_ballot_count(env, record.proposal_id, -1);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Voter(record.proposal_id, record.voter.clone()));
+        
+        VoteRetracted {
+            proposal_id: record.proposal_id,
+            voter: record.voter.clone(),
+            record: record.clone(),
+        }
+        .publish(env);
+        
+        Ok(())
+    }
+    
+    fn write_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, VOTER_RECORD_TTL_THRESHOLD, VOTER_RECORD_TTL);
+    }
+    
+    /// `proof` is only looked at in MerkleRoot polls
+    fn require_eligible(
+        env: &Env,
+        proposal: &Proposal,
+        voter: &Address,
+        proof: &Vec<BytesN<32>>,
+    ) -> Result<(), VoteError> {
+        match &proposal.config.eligibility {
+            Eligibility::Anyone => Ok(()),
+            Eligibility::Registered => {
+                if Self::is_registered(env.clone(), voter.clone()) {
+                    Ok(())
+                } else {
+                    Err(VoteError::NotEligible)
+                }
+            }
+            Eligibility::MerkleRoot(root, _) => {
+                if proof.len() > MAX_PROOF_LENGTH {
+                    return Err(VoteError::InvalidProof);
+                }
+                
+                // Hash from the voter's leaf up to the root
+                let mut node: BytesN<32> = env.crypto().sha256(&voter.clone().to_xdr(env)).into();
+                for sibling in proof.iter() {
+                    let mut pair = Bytes::new(env);
+                    if node < sibling {
+                        pair.append(&Bytes::from(node));
+                        pair.append(&Bytes::from(sibling));
+                    } else {
+                        pair.append(&Bytes::from(sibling));
+                        pair.append(&Bytes::from(node));
+                    }
+                    node = env.crypto().sha256(&pair).into();
+                }
+                
+                if node == *root {
+                    Ok(())
+                } else {
+                    Err(VoteError::InvalidProof)
+                }
+            }
+        }
+    }
+    
+    /// Ranked polls take their ballots through vote_ranked only
+    fn require_single_choice(proposal: &Proposal) -> Result<(), VoteError> {
+        if proposal.config.mode == VotingMode::RankedChoice {
+            return Err(VoteError::RankedBallot);
+        }
+        Ok(())
+    }
+    
+    /// Ballot changes and delegation would undermine a secret poll
+    fn require_open_ballot(proposal: &Proposal) -> Result<(), VoteError> {
+        if proposal.config.reveal_end_time.is_some() {
+            return Err(VoteError::SecretBallot);
+        }
+        Ok(())
+    }
+    
+    /// Secret polls keep every tally view closed until the reveal phase ends
+    fn require_results_visible(env: &Env, proposal: &Proposal) -> Result<(), VoteError> {
+        match proposal.config.reveal_end_time {
+            Some(reveal_end_time) if env.ledger().timestamp() < reveal_end_time => {
+                Err(VoteError::ResultsHidden)
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    fn require_open(env: &Env, proposal: &Proposal) -> Result<(), VoteError> {
+        if proposal.closed {
+            return Err(VoteError::PollClosed);
+        }
+        
+        let now = env.ledger().timestamp();
+        if now < proposal.config.start_time {
+            return Err(VoteError::PollNotOpen);
+        }
+        if now >= proposal.config.end_time {
+            return Err(VoteError::PollClosed);
+        }
+        Ok(())
+    }
+    
+    fn require_option(env: &Env, proposal_id: u32, option: &String) -> Result<(), VoteError> {
+        if Self::read_tally(env, proposal_id).contains_key(option.clone()) {
+            Ok(())
+        } else {
+            Err(VoteError::InvalidChoice)
+        }
+    }
+    
+    /// Store the record (filling in its live-until ledger) and extend its TTL
+    fn write_voter_record(env: &Env, record: &mut VoterRecord) {
+        Self::index_voter(env, record.proposal_id, &record.voter);
+        
+        record.live_until_ledger = Self::next_live_until(env, record.proposal_id, &record.voter);
+        let key = DataKey::Voter(record.proposal_id, record.voter.clone());
+        env.storage().persistent().set(&key, record);
+        
+        // extend_ttl(threshold, extend_to)
+        // If TTL < threshold, extend to extend_to
+        env.storage().persistent().extend_ttl(
+            &key, 
+            VOTER_RECORD_TTL_THRESHOLD,  // If less than 1 month left
+            VOTER_RECORD_TTL             // Extend to 1 year
+        );
+    }
+    
+    /// extend_ttl on an existing record and its voter index entries, plus the
+    /// TtlExtended event. The record is rewritten only if the host extends it.
+    fn extend_voter_record(env: &Env, proposal_id: u32, voter: &Address) {
+        let key = DataKey::Voter(proposal_id, voter.clone());
+        if let Some(mut record) = Self::read_voter(env, proposal_id, voter.clone()) {
+            let live_until = Self::next_live_until(env, proposal_id, voter);
+            if live_until != record.live_until_ledger {
+                record.live_until_ledger = live_until;
+                env.storage().persistent().set(&key, &record);
+            }
+        }
+        env.storage().persistent().extend_ttl(&key, VOTER_RECORD_TTL_THRESHOLD, VOTER_RECORD_TTL);
+        
+        // list_voters needs the index to outlive the records it points to
+        let slot_key = DataKey::VoterSlot(proposal_id, voter.clone());
+        if let Some(position) = env.storage().persistent().get::<DataKey, u32>(&slot_key) {
+            for index_key in [
+                slot_key,
+                DataKey::VoterAt(proposal_id, position),
+                DataKey::VoterIndexLen(proposal_id),
+            ] {
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&index_key, VOTER_RECORD_TTL_THRESHOLD, VOTER_RECORD_TTL);
+            }
+        }
+        
+        TtlExtended {
+            extension: TtlExtension {
+                proposal_id: Some(proposal_id),
+                voter: Some(voter.clone()),
+                extend_to: VOTER_RECORD_TTL,
+            },
+        }
+        .publish(env);
+    }
+    
+    /// The host's extend_ttl rule applied to the stored live-until ledger:
+    /// unchanged while at least the threshold is left, otherwise a full
+    /// VOTER_RECORD_TTL from now (which is also what a new record gets).
+    fn next_live_until(env: &Env, proposal_id: u32, voter: &Address) -> u32 {
+        let now = env.ledger().sequence();
+        match Self::read_voter(env, proposal_id, voter.clone()) {
+            Some(record) if record.live_until_ledger.saturating_sub(now) >= VOTER_RECORD_TTL_THRESHOLD => {
+                record.live_until_ledger
+            }
+            _ => now + VOTER_RECORD_TTL,
+        }
+    }
+    
+    /// Add `votes` to one option and to the poll total.
+    /// Checked explicitly rather than relying on the release profile's
+    /// overflow-checks; the whole call is rolled back on Err.
+    fn add_votes(env: &Env, proposal_id: u32, option: &String, votes: u64) -> Result<(), VoteError> {
+        let mut tally = Self::read_tally(env, proposal_id);
+        let current = tally.get(option.clone()).unwrap_or(0);
+        tally.set(option.clone(), current.checked_add(votes).ok_or(VoteError::Overflow)?);
+        
+        let total = Self::read_total_votes(env, proposal_id);
+        let total = total.checked_add(votes).ok_or(VoteError::Overflow)?;
+        
+        Self::write_tally(env, proposal_id, &tally);
+        Self::write_total_votes(env, proposal_id, total);
+        Ok(())
+    }
+    
+    /// Votes per option a ballot puts on the tally
+    fn ballot_split(env: &Env, record: &VoterRecord) -> Map<String, u64> {
+        if record.allocation.is_empty() {
+            Map::from_array(env, [(record.choice.clone(), record.votes)])
+        } else {
+            record.allocation.clone()
+        }
+    }
+    
+    /// `add_votes` for every option the ballot covers
+    fn add_ballot_votes(env: &Env, record: &VoterRecord) -> Result<(), VoteError> {
+        for (option, votes) in Self::ballot_split(env, record).iter() {
+            Self::add_votes(env, record.proposal_id, &option, votes)?;
+        }
+        Ok(())
+    }
+    
+    /// Undo `add_votes` for a ballot that is being moved or withdrawn
+    fn remove_votes(env: &Env, proposal_id: u32, option: &String, votes: u64) -> Result<(), VoteError> {
+        let mut tally = Self::read_tally(env, proposal_id);
+        let current = tally.get(option.clone()).unwrap_or(0);
+        tally.set(option.clone(), current.checked_sub(votes).ok_or(VoteError::Overflow)?);
+        
+        let total = Self::read_total_votes(env, proposal_id);
+        let total = total.checked_sub(votes).ok_or(VoteError::Overflow)?;
+        
+        Self::write_tally(env, proposal_id, &tally);
+        Self::write_total_votes(env, proposal_id, total);
+        Ok(())
+    }
+    
+    fn extend_proposal_ttl(env: &Env, proposal_id: u32) {
+        env.storage().persistent().extend_ttl(
+            &DataKey::Proposal(proposal_id),
+            PROPOSAL_TTL_THRESHOLD,
+            PROPOSAL_TTL
+        );
+        env.storage().instance().extend_ttl(
+            INSTANCE_TTL_THRESHOLD,  // If less than 1 month left
+            INSTANCE_TTL             // Extend to 1 year
+        );
+    }
+    
+    fn read_voter(env: &Env, proposal_id: u32, voter: Address) -> Option<VoterRecord> {
+        env.storage().persistent().get(&DataKey::Voter(proposal_id, voter))
+    }
+    
+    fn read_tally(env: &Env, proposal_id: u32) -> Map<String, u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Tally(proposal_id))
+            .unwrap_or(Map::new(env))
+    }
+    
+    fn write_tally(env: &Env, proposal_id: u32, tally: &Map<String, u64>) {
+        let key = DataKey::Tally(proposal_id);
+        env.storage().persistent().set(&key, tally);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+    }
+    
+    fn read_total_votes(env: &Env, proposal_id: u32) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TotalVotes(proposal_id))
+            .unwrap_or(0)
+    }
+    
+    fn read_committers(env: &Env, proposal_id: u32) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Committers(proposal_id))
+            .unwrap_or(Vec::new(env))
+    }
+    
+    fn write_total_votes(env: &Env, proposal_id: u32, total: u64) {
+        let key = DataKey::TotalVotes(proposal_id);
+        env.storage().persistent().set(&key, &total);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+    }
+    
+    /// `delta` is +1 for a new ballot and -1 for a removed one
+    fn add_ballot_count(env: &Env, proposal_id: u32, delta: i32) {
+        let count = Self::get_ballot_count(env.clone(), proposal_id);
+        let key = DataKey::BallotCount(proposal_id);
+        env.storage().persistent().set(&key, &count.saturating_add_signed(delta));
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+    }
+    
+    /// Instant runoff over the per-ranking totals. At most one round per
+    /// option, since each round eliminates at least one.
+    fn run_runoff(env: &Env, proposal: &Proposal) -> RunoffReport {
+        let options = &proposal.config.options;
+        let rankings = Self::read_rankings(env, proposal.id);
+        
+        let mut remaining: Vec<String> = proposal.config.options.clone();
+        let mut rounds: Vec<RunoffRound> = Vec::new(env);
+        loop {
+            let mut counts: Map<String, u64> = Map::new(env);
+            for option in remaining.iter() {
+                counts.set(option, 0);
+            }
+            let mut continuing: u64 = 0;
+            for (ranking, votes) in rankings.iter() {
+                // Highest preference still in the race, if any. Cannot overflow:
+                // every round sums a subset of the ballots behind the total.
+                let mut preferences = ranking.iter().map(|position| options.get_unchecked(position as u32));
+                if let Some(option) = preferences.find(|o| counts.contains_key(o.clone())) {
+                    counts.set(option.clone(), counts.get(option).unwrap() + votes);
+                    continuing += votes;
+                }
+            }
+            
+            let mut most: u64 = 0;
+            let mut fewest: u64 = u64::MAX;
+            let mut leader: Option<String> = None;
+            for (option, votes) in counts.iter() {
+                if votes > most {
+                    most = votes;
+                    leader = Some(option);
+                }
+                fewest = fewest.min(votes);
+            }
+            
+            if (most as u128) * 2 > continuing as u128 || remaining.len() == 1 {
+                rounds.push_back(RunoffRound { counts, eliminated: Vec::new(env) });
+                return RunoffReport { rounds, winner: leader };
+            }
+            
+            let mut eliminated: Vec<String> = Vec::new(env);
+            let mut survivors: Vec<String> = Vec::new(env);
+            for option in remaining.iter() {
+                if counts.get(option.clone()).unwrap() == fewest {
+                    eliminated.push_back(option);
+                } else {
+                    survivors.push_back(option);
+                }
+            }
+            
+            // Everyone left ties for last: no winner
+            if survivors.is_empty() {
+                rounds.push_back(RunoffRound { counts, eliminated: Vec::new(env) });
+                return RunoffReport { rounds, winner: None };
+            }
+            
+            rounds.push_back(RunoffRound { counts, eliminated });
+            remaining = survivors;
+        }
+    }
+    
+    fn read_rankings(env: &Env, proposal_id: u32) -> Map<Bytes, u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Rankings(proposal_id))
+            .unwrap_or(Map::new(env))
+    }
+    
+    /// A ranking as the positions of its options in the ProposalConfig,
+    /// one byte each (ranked polls have at most MAX_RANKED_OPTIONS)
+    fn ranking_key(env: &Env, proposal: &Proposal, ranking: &Vec<String>) -> Bytes {
+        let mut key = Bytes::new(env);
+        for option in ranking.iter() {
+            key.push_back(proposal.config.options.first_index_of(option).unwrap() as u8);
+        }
+        key
+    }
+    
+    /// Count a ranked ballot towards its ranking (no-op for other ballots)
+    fn add_ranking(env: &Env, proposal: &Proposal, record: &VoterRecord) -> Result<(), VoteError> {
+        if record.ranking.is_empty() {
+            return Ok(());
+        }
+        let key = Self::ranking_key(env, proposal, &record.ranking);
+        let mut rankings = Self::read_rankings(env, proposal.id);
+        let votes = rankings.get(key.clone()).unwrap_or(0);
+        rankings.set(key, votes.checked_add(record.votes).ok_or(VoteError::Overflow)?);
+        Self::write_rankings(env, proposal.id, &rankings);
+        Ok(())
+    }
+    
+    /// Undo `add_ranking` for a withdrawn ballot
+    fn remove_ranking(env: &Env, proposal: &Proposal, record: &VoterRecord) -> Result<(), VoteError> {
+        let key = Self::ranking_key(env, proposal, &record.ranking);
+        let mut rankings = Self::read_rankings(env, proposal.id);
+        let votes = rankings.get(key.clone()).unwrap_or(0);
+        match votes.checked_sub(record.votes).ok_or(VoteError::Overflow)? {
+            0 => {
+                rankings.remove(key);
+            }
+            left => rankings.set(key, left),
+        }
+        Self::write_rankings(env, proposal.id, &rankings);
+        Ok(())
+    }
+    
+    fn write_rankings(env: &Env, proposal_id: u32, rankings: &Map<Bytes, u64>) {
+        let key = DataKey::Rankings(proposal_id);
+        env.storage().persistent().set(&key, rankings);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL);
+    }
+    
+    /// Append the voter to the poll's index unless they are already in it
+    fn index_voter(env: &Env, proposal_id: u32, voter: &Address) {
+        let slot_key = DataKey::VoterSlot(proposal_id, voter.clone());
+        if env.storage().persistent().has(&slot_key) {
+            return;
+        }
+        
+        let position = Self::get_voter_index_len(env.clone(), proposal_id);
+        Self::write_persistent(env, &DataKey::VoterAt(proposal_id, position), voter);
+        Self::write_persistent(env, &slot_key, &position);
+        Self::write_persistent(env, &DataKey::VoterIndexLen(proposal_id), &(position + 1));
+    }
+    
+    fn add_registration(env: &Env, voter: Address) {
+        let key = DataKey::Registered(voter);
+        if !env.storage().persistent().has(&key) {
+            let count = Self::get_registered_count(env.clone());
+            env.storage().instance().set(&REGISTERED_COUNT, &(count + 1));
+        }
+        Self::write_persistent(env, &key, &true);
+    }
+    
+    fn quorum_met(env: &Env, proposal: &Proposal) -> bool {
+        let ballots = Self::get_ballot_count(env.clone(), proposal.id);
+        match proposal.config.quorum {
+            Quorum::None => true,
+            Quorum::Ballots(minimum) => ballots >= minimum,
+            Quorum::Percent(percent) => {
+                ballots as u64 * 100 >= percent as u64 * proposal.electorate as u64
+            }
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//                                  TESTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        map,
+        testutils::{storage::Persistent as _, Address as _, EnvTestConfig, Events, Ledger},
+        vec, Env, IntoVal,
+    };
+    use proptest::prelude::*;
+
+    const START: u64 = 1_000;
+    const END: u64 = 2_000;
+
+    fn config(env: &Env, title: &str) -> ProposalConfig {
+        ProposalConfig {
+            title: String::from_str(env, title),
+            options: vec![env, String::from_str(env, "yes"), String::from_str(env, "no")],
+            mode: VotingMode::Standard,
+            start_time: START,
+            end_time: END,
+            reveal_end_time: None,
+            eligibility: Eligibility::Anyone,
+            quorum: Quorum::None,
+            threshold: Threshold::Plurality,
+        }
+    }
+
+    fn set_time(env: &Env, timestamp: u64) {
+        env.ledger().with_mut(|li| li.timestamp = timestamp);
+    }
+
+    /// Registers the contract and creates one poll that is currently open
+    fn setup(env: &Env) -> (VoteContractClient<'_>, u32) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(VoteContract, (&admin,));
+        let client = VoteContractClient::new(env, &contract_id);
+        
+        let creator = Address::generate(env);
+        let proposal_id = client.create_proposal(&creator, &config(env, "Adopt the roadmap?"));
+        set_time(env, START);
+        
+        (client, proposal_id)
+    }
+
+    #[test]
+    fn test_vote_yes() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        client.vote(&alice, &id, &yes, &3);
+        
+        assert_eq!(client.get_option_votes(&id, &yes), 3);
+        assert_eq!(client.get_option_votes(&id, &String::from_str(&env, "no")), 0);
+        assert_eq!(client.get_total_votes(&id), 3);
+        assert!(client.has_voted(&id, &alice));
+    }
+    
+    #[test]
+    fn test_vote_no() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let bob = Address::generate(&env);
+        let no = String::from_str(&env, "no");
+        
+        client.vote(&bob, &id, &no, &5);
+        
+        assert_eq!(client.get_option_votes(&id, &String::from_str(&env, "yes")), 0);
+        assert_eq!(client.get_option_votes(&id, &no), 5);
+        assert_eq!(client.get_total_votes(&id), 5);
+    }
+    
+    #[test]
+    fn test_multiple_voters() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let charlie = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        client.vote(&alice, &id, &yes, &3);
+        client.vote(&bob, &id, &no, &5);
+        client.vote(&charlie, &id, &yes, &2);
+        
+        assert_eq!(client.get_option_votes(&id, &yes), 5);
+        assert_eq!(client.get_option_votes(&id, &no), 5);
+        assert_eq!(client.get_total_votes(&id), 10);
+        
+        // A tie comes back as both options
+        set_time(&env, END);
+        assert_eq!(client.get_winner(&id), PollOutcome::Tie(vec![&env, yes, no]));
+    }
+    
+    #[test]
+    fn test_get_voter_record() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        client.vote(&alice, &id, &yes, &4);
+        
+        let record = client.get_voter(&id, &alice);
+        assert!(record.is_some());
+        
+        let record = record.unwrap();
+        assert_eq!(record.proposal_id, id);
+        assert_eq!(record.voter, alice);
+        assert_eq!(record.choice, yes);
+        assert_eq!(record.votes, 4);
+        assert_eq!(record.credits_spent, 0);
+        assert_eq!(record.cast_by, alice);
+    }
+    
+    #[test]
+    fn test_cannot_vote_twice() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        client.vote(&alice, &id, &yes, &3);
+        assert_eq!(
+            client.try_vote(&alice, &id, &no, &2),
+            Err(Ok(VoteError::AlreadyVoted))
+        );
+    }
+    
+    #[test]
+    fn test_max_votes() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        assert_eq!(
+            client.try_vote(&alice, &id, &yes, &10),
+            Err(Ok(VoteError::TooManyVotes))
+        );
+    }
+    
+    #[test]
+    fn test_min_votes() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        assert_eq!(
+            client.try_vote(&alice, &id, &yes, &0),
+            Err(Ok(VoteError::TooFewVotes))
+        );
+    }
+    
+    #[test]
+    fn test_invalid_choice() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let invalid = String::from_str(&env, "maybe");
+        
+        assert_eq!(
+            client.try_vote(&alice, &id, &invalid, &3),
+            Err(Ok(VoteError::InvalidChoice))
+        );
+        assert!(!client.has_voted(&id, &alice));
+    }
+    
+    #[test]
+    fn test_multiple_proposals_are_independent() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, first) = setup(&env);
+        let creator = Address::generate(&env);
+        let second = client.create_proposal(&creator, &config(&env, "Raise the budget?"));
+        
+        assert_eq!(first, 1);
+        assert_eq!(second, 2);
+        assert_eq!(client.get_proposal_count(), 2);
+        assert_eq!(client.get_proposal(&second).creator, creator);
+        
+        // The same voter can take part in every poll, once each
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        client.vote(&alice, &first, &yes, &3);
+        client.vote(&alice, &second, &no, &2);
+        
+        assert_eq!(client.get_results(&first), map![&env, (yes.clone(), 3), (no.clone(), 0)]);
+        assert_eq!(client.get_results(&second), map![&env, (yes.clone(), 0), (no.clone(), 2)]);
+        
+        set_time(&env, END);
+        assert_eq!(client.get_winner(&first), PollOutcome::Passed(yes));
+        assert_eq!(client.get_winner(&second), PollOutcome::Passed(no.clone()));
+        assert_eq!(client.get_voter(&second, &alice).unwrap().choice, no);
+    }
+    
+    #[test]
+    fn test_unknown_proposal() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        assert_eq!(
+            client.try_vote(&alice, &99, &yes, &1),
+            Err(Ok(VoteError::ProposalNotFound))
+        );
+        assert_eq!(client.try_get_results(&99), Err(Ok(VoteError::ProposalNotFound)));
+        assert_eq!(client.try_get_winner(&99), Err(Ok(VoteError::ProposalNotFound)));
+    }
+    
+    #[test]
+    fn test_voting_window() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        // Before the window opens
+        set_time(&env, START - 1);
+        assert!(!client.is_open(&id));
+        assert_eq!(client.try_vote(&alice, &id, &yes, &1), Err(Ok(VoteError::PollNotOpen)));
+        
+        // Last second of the window is still open
+        set_time(&env, END - 1);
+        assert!(client.is_open(&id));
+        client.vote(&alice, &id, &yes, &1);
+        
+        // end_time itself is already closed
+        set_time(&env, END);
+        assert!(!client.is_open(&id));
+        assert_eq!(client.try_vote(&bob, &id, &yes, &1), Err(Ok(VoteError::PollClosed)));
+    }
+    
+    #[test]
+    fn test_winner_only_after_close() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let no = String::from_str(&env, "no");
+        client.vote(&alice, &id, &no, &2);
+        
+        // Live totals are visible, but there is no winner yet
+        assert_eq!(client.get_option_votes(&id, &no), 2);
+        assert_eq!(client.try_get_winner(&id), Err(Ok(VoteError::PollNotClosed)));
+        
+        set_time(&env, END);
+        assert_eq!(client.get_winner(&id), PollOutcome::Passed(no));
+    }
+    
+    #[test]
+    fn test_invalid_window() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        let mut bad = config(&env, "Backwards poll");
+        bad.end_time = bad.start_time;
+        assert_eq!(
+            client.try_create_proposal(&creator, &bad),
+            Err(Ok(VoteError::InvalidWindow))
+        );
+    }
+    
+    #[test]
+    fn test_multi_option_ranking() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        let red = String::from_str(&env, "red");
+        let green = String::from_str(&env, "green");
+        let blue = String::from_str(&env, "blue");
+        let black = String::from_str(&env, "black");
+        
+        let mut cfg = config(&env, "Pick a colour");
+        cfg.options = vec![&env, red.clone(), green.clone(), blue.clone(), black.clone()];
+        let id = client.create_proposal(&creator, &cfg);
+        
+        client.vote(&Address::generate(&env), &id, &green, &4);
+        client.vote(&Address::generate(&env), &id, &blue, &4);
+        client.vote(&Address::generate(&env), &id, &red, &4);
+        client.vote(&Address::generate(&env), &id, &black, &1);
+        
+        // Three-way tie for first: ranks go 1, 1, 1, 4
+        assert_eq!(
+            client.get_ranked_results(&id),
+            vec![
+                &env,
+                RankedOption { rank: 1, option: red.clone(), votes: 4 },
+                RankedOption { rank: 1, option: green.clone(), votes: 4 },
+                RankedOption { rank: 1, option: blue.clone(), votes: 4 },
+                RankedOption { rank: 4, option: black.clone(), votes: 1 },
+            ]
+        );
+        
+        set_time(&env, END);
+        assert_eq!(client.get_winner(&id), PollOutcome::Tie(vec![&env, red, green, blue]));
+        
+        // "yes" is not on this ballot
+        assert_eq!(client.get_option_votes(&id, &String::from_str(&env, "yes")), 0);
+    }
+    
+    #[test]
+    fn test_invalid_options() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        let a = String::from_str(&env, "a");
+        
+        let mut single = config(&env, "Only one option");
+        single.options = vec![&env, a.clone()];
+        assert_eq!(
+            client.try_create_proposal(&creator, &single),
+            Err(Ok(VoteError::InvalidOptions))
+        );
+        
+        let mut duplicate = config(&env, "Duplicate option");
+        duplicate.options = vec![&env, a.clone(), String::from_str(&env, "b"), a];
+        assert_eq!(
+            client.try_create_proposal(&creator, &duplicate),
+            Err(Ok(VoteError::InvalidOptions))
+        );
+    }
+    
+    #[test]
+    fn test_quadratic_voting() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        let mut cfg = config(&env, "Fund the grant?");
+        cfg.mode = VotingMode::Quadratic(100);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        // 10 votes cost the whole budget of 100 credits
+        client.vote(&alice, &id, &yes, &10);
+        let record = client.get_voter(&id, &alice).unwrap();
+        assert_eq!(record.votes, 10);
+        assert_eq!(record.credits_spent, 100);
+        
+        // 11 votes would cost 121 credits
+        assert_eq!(
+            client.try_vote(&bob, &id, &no, &11),
+            Err(Ok(VoteError::InsufficientCredits))
+        );
+        
+        // A huge count must not overflow the cost calculation
+        assert_eq!(
+            client.try_vote(&bob, &id, &no, &u64::MAX),
+            Err(Ok(VoteError::InsufficientCredits))
+        );
+        
+        // The standard 1-5 cap does not apply in quadratic mode
+        client.vote(&bob, &id, &no, &7);
+        client.vote(&carol, &id, &no, &1);
+        assert_eq!(client.get_voter(&id, &bob).unwrap().credits_spent, 49);
+        
+        assert_eq!(client.get_option_votes(&id, &yes), 10);
+        assert_eq!(client.get_option_votes(&id, &no), 8);
+        assert_eq!(client.get_total_votes(&id), 18);
+    }
+    
+    #[test]
+    fn test_quadratic_split() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, standard_id) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        let mut cfg = config(&env, "Fund the grant?");
+        cfg.mode = VotingMode::Quadratic(100);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        let dave = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        // 6 + 8 votes cost 36 + 64 = 100 credits, where 14 on one option would cost 196
+        let split = map![&env, (yes.clone(), 6), (no.clone(), 8)];
+        client.vote_quadratic(&alice, &id, &split);
+        let record = client.get_voter(&id, &alice).unwrap();
+        assert_eq!(record.choice, no);
+        assert_eq!(record.votes, 14);
+        assert_eq!(record.credits_spent, 100);
+        assert_eq!(record.allocation, split);
+        assert_eq!(client.get_results(&id), map![&env, (yes.clone(), 6), (no.clone(), 8)]);
+        
+        // 64 + 49 = 113 credits is over budget
+        assert_eq!(
+            client.try_vote_quadratic(&bob, &id, &map![&env, (yes.clone(), 8), (no.clone(), 7)]),
+            Err(Ok(VoteError::InsufficientCredits))
+        );
+        
+        // Every listed option needs a vote, and must be on the ballot
+        assert_eq!(
+            client.try_vote_quadratic(&bob, &id, &map![&env, (yes.clone(), 3), (no.clone(), 0)]),
+            Err(Ok(VoteError::TooFewVotes))
+        );
+        assert_eq!(client.try_vote_quadratic(&bob, &id, &Map::new(&env)), Err(Ok(VoteError::TooFewVotes)));
+        assert_eq!(
+            client.try_vote_quadratic(&bob, &id, &map![&env, (String::from_str(&env, "maybe"), 1)]),
+            Err(Ok(VoteError::InvalidChoice))
+        );
+        assert_eq!(
+            client.try_vote_quadratic(&bob, &standard_id, &map![&env, (yes.clone(), 1)]),
+            Err(Ok(VoteError::NotQuadratic))
+        );
+        
+        // A split cannot be moved as a whole, only retracted
+        assert_eq!(client.try_change_vote(&alice, &id, &yes), Err(Ok(VoteError::SplitBallot)));
+        client.retract_vote(&alice, &id);
+        assert_eq!(client.get_total_votes(&id), 0);
+        
+        // Delegators get the same split
+        client.delegate(&carol, &id, &dave);
+        client.vote_quadratic(&dave, &id, &map![&env, (yes.clone(), 3), (no.clone(), 4)]);
+        let record = client.get_voter(&id, &carol).unwrap();
+        assert_eq!(record.credits_spent, 25);
+        assert_eq!(record.cast_by, dave);
+        assert_eq!(client.get_results(&id), map![&env, (yes, 6), (no, 8)]);
+    }
+    
+    #[test]
+    fn test_token_weighted_voting() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        // Any SEP-41 token works; the Stellar Asset Contract is the quickest to mint
+        let token_admin = Address::generate(&env);
+        let token_id = env.register_stellar_asset_contract_v2(token_admin).address();
+        let token = token::StellarAssetClient::new(&env, &token_id);
+        
+        let mut cfg = config(&env, "Upgrade the treasury?");
+        cfg.mode = VotingMode::TokenWeighted(token_id);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        let whale = Address::generate(&env);
+        let minnow = Address::generate(&env);
+        let nobody = Address::generate(&env);
+        token.mint(&whale, &1_000);
+        token.mint(&minnow, &30);
+        
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        // Voters can use up to their full balance
+        client.vote(&whale, &id, &yes, &1_000);
+        client.vote(&minnow, &id, &no, &20);
+        
+        assert_eq!(client.get_voter(&id, &whale).unwrap().votes, 1_000);
+        assert_eq!(client.get_voter(&id, &minnow).unwrap().votes, 20);
+        assert_eq!(client.get_option_votes(&id, &yes), 1_000);
+        assert_eq!(client.get_option_votes(&id, &no), 20);
+        
+        // No tokens, no vote
+        assert_eq!(
+            client.try_vote(&nobody, &id, &yes, &1),
+            Err(Ok(VoteError::InsufficientVotingPower))
+        );
+        
+        // Counted tokens sit in the contract, so they cannot vote again from a fresh address
+        let balances = token::TokenClient::new(&env, &token.address);
+        assert_eq!(balances.balance(&whale), 0);
+        assert_eq!(balances.balance(&minnow), 10);
+        assert_eq!(client.get_locked(&id, &whale), 1_000);
+        assert!(balances.try_transfer(&whale, &nobody, &1_000).is_err());
+        
+        // Retracting hands them back; otherwise they wait for the poll to end
+        client.retract_vote(&minnow, &id);
+        assert_eq!(balances.balance(&minnow), 30);
+        assert_eq!(client.try_withdraw_tokens(&whale, &id), Err(Ok(VoteError::PollNotClosed)));
+        
+        set_time(&env, END);
+        assert_eq!(client.withdraw_tokens(&whale, &id), 1_000);
+        assert_eq!(balances.balance(&whale), 1_000);
+        assert_eq!(client.try_withdraw_tokens(&whale, &id), Err(Ok(VoteError::NothingLocked)));
+        assert_eq!(client.get_option_votes(&id, &yes), 1_000);
+    }
+    
+    #[test]
+    fn test_change_vote() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        client.vote(&alice, &id, &yes, &4);
+        client.vote(&bob, &id, &yes, &1);
+        
+        set_time(&env, START + 60);
+        client.change_vote(&alice, &id, &no);
+        
+        // Alice's 4 votes moved from yes to no; the total is unchanged
+        assert_eq!(client.get_option_votes(&id, &yes), 1);
+        assert_eq!(client.get_option_votes(&id, &no), 4);
+        assert_eq!(client.get_total_votes(&id), 5);
+        
+        let record = client.get_voter(&id, &alice).unwrap();
+        assert_eq!(record.choice, no);
+        assert_eq!(record.votes, 4);
+        assert_eq!(record.timestamp, START + 60);
+        
+        // Only voters with a ballot can change it, and only to a real option
+        let carol = Address::generate(&env);
+        assert_eq!(client.try_change_vote(&carol, &id, &no), Err(Ok(VoteError::VoterNotFound)));
+        assert_eq!(
+            client.try_change_vote(&alice, &id, &String::from_str(&env, "maybe")),
+            Err(Ok(VoteError::InvalidChoice))
+        );
+        
+        // No changes once the poll has closed
+        set_time(&env, END);
+        assert_eq!(client.try_change_vote(&alice, &id, &yes), Err(Ok(VoteError::PollClosed)));
+    }
+    
+    #[test]
+    fn test_retract_vote() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        client.vote(&alice, &id, &yes, &3);
+        client.retract_vote(&alice, &id);
+        
+        assert!(!client.has_voted(&id, &alice));
+        assert_eq!(client.get_option_votes(&id, &yes), 0);
+        assert_eq!(client.get_total_votes(&id), 0);
+        assert_eq!(client.try_retract_vote(&alice, &id), Err(Ok(VoteError::VoterNotFound)));
+        
+        // After retracting, Alice can cast a fresh ballot
+        client.vote(&alice, &id, &no, &2);
+        assert_eq!(client.get_option_votes(&id, &no), 2);
+        
+        set_time(&env, END);
+        assert_eq!(client.try_retract_vote(&alice, &id), Err(Ok(VoteError::PollClosed)));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn test_change_vote_requires_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        let alice = Address::generate(&env);
+        client.vote(&alice, &id, &String::from_str(&env, "yes"), &1);
+        
+        // Drop the mocked auths: nobody has signed for Alice now
+        env.set_auths(&[]);
+        client.change_vote(&alice, &id, &String::from_str(&env, "no"));
+    }
+    
+    #[test]
+    fn test_transitive_delegation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        // alice → bob → carol; dave votes on his own
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        let dave = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        client.delegate(&alice, &id, &bob);
+        client.delegate(&bob, &id, &carol);
+        assert_eq!(client.get_delegate(&id, &alice), Some(bob.clone()));
+        assert_eq!(client.get_delegators(&id, &carol), vec![&env, bob.clone()]);
+        
+        // Delegators cannot vote themselves while delegated
+        assert_eq!(client.try_vote(&alice, &id, &yes, &1), Err(Ok(VoteError::AlreadyDelegated)));
+        
+        client.vote(&carol, &id, &yes, &3);
+        client.vote(&dave, &id, &no, &2);
+        
+        // Carol's 3 votes count for carol, bob and alice
+        assert_eq!(client.get_option_votes(&id, &yes), 9);
+        assert_eq!(client.get_total_votes(&id), 11);
+        
+        let record = client.get_voter(&id, &alice).unwrap();
+        assert_eq!(record.choice, yes);
+        assert_eq!(record.votes, 3);
+        assert_eq!(record.cast_by, carol);
+        assert_eq!(client.get_voter(&id, &carol).unwrap().cast_by, carol);
+        
+        // A delegated ballot belongs to the delegate
+        assert_eq!(client.try_change_vote(&alice, &id, &no), Err(Ok(VoteError::CastByDelegate)));
+        assert_eq!(client.try_retract_vote(&bob, &id), Err(Ok(VoteError::CastByDelegate)));
+        
+        // Changing the delegate's vote moves every ballot they cast
+        client.change_vote(&carol, &id, &no);
+        assert_eq!(client.get_option_votes(&id, &yes), 0);
+        assert_eq!(client.get_option_votes(&id, &no), 11);
+        assert_eq!(client.get_voter(&id, &alice).unwrap().choice, no);
+        
+        // Bob takes his vote back: his ballot and alice's (cast by carol) go away
+        client.undelegate(&bob, &id);
+        assert!(!client.has_voted(&id, &bob));
+        assert!(!client.has_voted(&id, &alice));
+        assert_eq!(client.get_option_votes(&id, &no), 5);
+        
+        // Bob now votes himself, which also covers alice
+        client.vote(&bob, &id, &yes, &1);
+        assert_eq!(client.get_voter(&id, &alice).unwrap().cast_by, bob);
+        assert_eq!(client.get_option_votes(&id, &yes), 2);
+        
+        // Retracting withdraws the ballots cast for delegators too
+        client.retract_vote(&bob, &id);
+        assert!(!client.has_voted(&id, &alice));
+        assert_eq!(client.get_option_votes(&id, &yes), 0);
+        assert_eq!(client.get_total_votes(&id), 5);
+    }
+    
+    #[test]
+    fn test_delegation_rules() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+        let d = Address::generate(&env);
+        let e = Address::generate(&env);
+        let f = Address::generate(&env);
+        
+        // Cycles, including delegating to yourself
+        assert_eq!(client.try_delegate(&a, &id, &a), Err(Ok(VoteError::DelegationCycle)));
+        client.delegate(&a, &id, &b);
+        client.delegate(&b, &id, &c);
+        assert_eq!(client.try_delegate(&c, &id, &a), Err(Ok(VoteError::DelegationCycle)));
+        assert_eq!(client.try_delegate(&a, &id, &c), Err(Ok(VoteError::AlreadyDelegated)));
+        
+        // a → b → c → d → e is 4 links, the maximum
+        client.delegate(&c, &id, &d);
+        client.delegate(&d, &id, &e);
+        assert_eq!(client.try_delegate(&e, &id, &f), Err(Ok(VoteError::DelegationTooDeep)));
+        
+        // Shorten it to a → b → c → d; x → f may not hang below a (x → f → a … d)
+        client.undelegate(&d, &id);
+        assert_eq!(client.try_undelegate(&d, &id), Err(Ok(VoteError::NotDelegated)));
+        let x = Address::generate(&env);
+        client.delegate(&x, &id, &f);
+        assert_eq!(client.try_delegate(&f, &id, &a), Err(Ok(VoteError::DelegationTooDeep)));
+        client.delegate(&f, &id, &b);
+        
+        // Delegating to someone who has already voted is too late
+        let g = Address::generate(&env);
+        let h = Address::generate(&env);
+        client.vote(&h, &id, &String::from_str(&env, "yes"), &1);
+        assert_eq!(client.try_delegate(&g, &id, &h), Err(Ok(VoteError::DelegateAlreadyVoted)));
+        assert_eq!(client.try_delegate(&h, &id, &g), Err(Ok(VoteError::AlreadyVoted)));
+    }
+    
+    #[test]
+    fn test_delegator_cap() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        let target = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        // Seven direct delegators plus one through a chain fill the tree
+        for _ in 0..MAX_DELEGATORS - 2 {
+            client.delegate(&Address::generate(&env), &id, &target);
+        }
+        let middle = Address::generate(&env);
+        client.delegate(&middle, &id, &target);
+        client.delegate(&Address::generate(&env), &id, &middle);
+        assert_eq!(
+            client.try_delegate(&Address::generate(&env), &id, &target),
+            Err(Ok(VoteError::TooManyDelegators))
+        );
+        
+        // Counted at the top of the chain, whichever address is delegated to
+        assert_eq!(
+            client.try_delegate(&Address::generate(&env), &id, &middle),
+            Err(Ok(VoteError::TooManyDelegators))
+        );
+        
+        // A whole subtree joining counts all of its members: 6 + 3 is too many
+        client.undelegate(&middle, &id);
+        let other = Address::generate(&env);
+        client.delegate(&Address::generate(&env), &id, &other);
+        client.delegate(&Address::generate(&env), &id, &other);
+        assert_eq!(
+            client.try_delegate(&other, &id, &target),
+            Err(Ok(VoteError::TooManyDelegators))
+        );
+        client.delegate(&middle, &id, &target);
+        
+        // The target can still vote for everyone in a capped tree
+        client.vote(&target, &id, &yes, &1);
+        assert_eq!(client.get_ballot_count(&id), MAX_DELEGATORS + 1);
+    }
+    
+    #[test]
+    fn test_token_weighted_delegation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        let token_admin = Address::generate(&env);
+        let token_id = env.register_stellar_asset_contract_v2(token_admin).address();
+        let token = token::StellarAssetClient::new(&env, &token_id);
+        
+        let mut cfg = config(&env, "Upgrade the treasury?");
+        cfg.mode = VotingMode::TokenWeighted(token_id);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        let holder = Address::generate(&env);
+        let rep = Address::generate(&env);
+        token.mint(&holder, &500);
+        token.mint(&rep, &10);
+        
+        client.delegate(&holder, &id, &rep);
+        client.vote(&rep, &id, &String::from_str(&env, "yes"), &10);
+        
+        // The holder's full balance is counted through the representative
+        assert_eq!(client.get_voter(&id, &holder).unwrap().votes, 500);
+        assert_eq!(client.get_total_votes(&id), 510);
+        
+        // It was locked when delegating, and comes back on undelegate
+        let balances = token::TokenClient::new(&env, &token.address);
+        assert_eq!(balances.balance(&holder), 0);
+        client.undelegate(&holder, &id);
+        assert_eq!(balances.balance(&holder), 500);
+        assert_eq!(client.get_total_votes(&id), 10);
+        
+        // A balance that does not fit a u64 vote is an error, not a maximal vote
+        let whale = Address::generate(&env);
+        token.mint(&whale, &(u64::MAX as i128 + 1));
+        assert_eq!(
+            client.try_delegate(&whale, &id, &Address::generate(&env)),
+            Err(Ok(VoteError::Overflow))
+        );
+    }
+    
+    #[test]
+    fn test_vote_cast_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        let contract = client.address.clone();
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        client.delegate(&bob, &id, &alice);
+        client.vote(&alice, &id, &yes, &2);
+        let events = env.events().all();
+        
+        let alice_record = client.get_voter(&id, &alice).unwrap();
+        let bob_record = client.get_voter(&id, &bob).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                &env,
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "vote_cast"), id, alice.clone()).into_val(&env),
+                    alice_record.into_val(&env),
+                ),
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "vote_cast"), id, bob.clone()).into_val(&env),
+                    bob_record.into_val(&env),
+                ),
+            ]
+        );
+    }
+    
+    #[test]
+    fn test_vote_change_and_retract_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        let contract = client.address.clone();
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        // Bob's ballot is cast by alice, so it follows her change and retraction
+        client.delegate(&bob, &id, &alice);
+        client.vote(&alice, &id, &yes, &2);
+        let alice_record = client.get_voter(&id, &alice).unwrap();
+        let bob_record = client.get_voter(&id, &bob).unwrap();
+        
+        set_time(&env, START + 5);
+        client.change_vote(&alice, &id, &no);
+        let events = env.events().all();
+        let mut changed = alice_record.clone();
+        changed.choice = no.clone();
+        changed.timestamp = START + 5;
+        let mut bob_changed = bob_record.clone();
+        bob_changed.choice = no.clone();
+        bob_changed.timestamp = START + 5;
+        assert_eq!(
+            events,
+            vec![
+                &env,
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "vote_changed"), id, alice.clone()).into_val(&env),
+                    VoteChange { previous_choice: yes.clone(), record: changed.clone() }.into_val(&env),
+                ),
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "vote_changed"), id, bob.clone()).into_val(&env),
+                    VoteChange { previous_choice: yes.clone(), record: bob_changed.clone() }.into_val(&env),
+                ),
+            ]
+        );
+        
+        client.retract_vote(&alice, &id);
+        let events = env.events().all();
+        assert_eq!(
+            events,
+            vec![
+                &env,
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "vote_retracted"), id, alice.clone()).into_val(&env),
+                    changed.into_val(&env),
+                ),
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "vote_retracted"), id, bob.clone()).into_val(&env),
+                    bob_changed.into_val(&env),
+                ),
+            ]
+        );
+    }
+    
+    #[test]
+    fn test_ttl_and_close_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        let contract = client.address.clone();
+        
+        let alice = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        client.vote(&alice, &id, &yes, &3);
+        
+        client.extend_contract_ttl();
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "ttl_extended"),).into_val(&env),
+                    TtlExtension { proposal_id: None, voter: None, extend_to: INSTANCE_TTL }.into_val(&env),
+                ),
+            ]
+        );
+        
+        client.extend_voter_ttl(&id, &alice);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "ttl_extended"),).into_val(&env),
+                    TtlExtension {
+                        proposal_id: Some(id),
+                        voter: Some(alice.clone()),
+                        extend_to: VOTER_RECORD_TTL,
+                    }
+                    .into_val(&env),
+                ),
+            ]
+        );
+        
+        set_time(&env, START + 10);
+        let creator = client.get_proposal(&id).creator;
+        client.close_poll(&creator, &id);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract.clone(),
+                    (Symbol::new(&env, "poll_closed"), id).into_val(&env),
+                    PollSummary {
+                        tally: map![&env, (yes, 3), (no, 0)],
+                        total_votes: 3,
+                        closed_at: START + 10,
+                    }
+                    .into_val(&env),
+                ),
+            ]
+        );
+    }
+    
+    #[test]
+    fn test_close_poll() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, id) = setup(&env);
+        let creator = client.get_proposal(&id).creator;
+        let stranger = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        // Only the creator may end the poll before end_time
+        assert_eq!(client.try_close_poll(&stranger, &id), Err(Ok(VoteError::Unauthorized)));
+        
+        set_time(&env, START + 10);
+        client.close_poll(&creator, &id);
+        
+        // Closed early: no more ballots, and the result is final right away
+        assert!(!client.is_open(&id));
+        assert_eq!(client.get_proposal(&id).config.end_time, START + 10);
+        assert_eq!(
+            client.try_vote(&stranger, &id, &yes, &1),
+            Err(Ok(VoteError::PollClosed))
+        );
+        assert_eq!(client.get_winner(&id), PollOutcome::Tie(vec![&env, yes, String::from_str(&env, "no")]));
+        assert_eq!(client.try_close_poll(&creator, &id), Err(Ok(VoteError::PollClosed)));
+        
+        // After end_time anyone may publish the close
+        let creator2 = Address::generate(&env);
+        let second = client.create_proposal(&creator2, &config(&env, "Second poll"));
+        set_time(&env, END);
+        client.close_poll(&stranger, &second);
+    }
+    
+    #[test]
+    fn test_commit_reveal() {
+        let env = Env::default();
+        env.mock_all_auths();
         
-        // ═══════════════════════════════════════════════════════════════════
-        //                     VALIDATION CHECKS
-        // ═══════════════════════════════════════════════════════════════════
-        assert!(vote_count >= 1, "Must cast at least 1 vote");
-        assert!(vote_count <= 5, "Maximum 5 votes allowed");
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        
+        let mut cfg = config(&env, "Elect the chair");
+        cfg.reveal_end_time = Some(END + 500);
+        let id = client.create_proposal(&creator, &cfg);
         
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
         let yes = String::from_str(&env, "yes");
         let no = String::from_str(&env, "no");
-        assert!(choice == yes || choice == no, "Choice must be 'yes' or 'no'");
+        let salt_a = BytesN::from_array(&env, &[1; 32]);
+        let salt_b = BytesN::from_array(&env, &[2; 32]);
+        let salt_c = BytesN::from_array(&env, &[3; 32]);
         
-        let existing: Option<VoterRecord> = env.storage().persistent().get(&voter);
-        assert!(existing.is_none(), "You have already voted!");
+        // Commit phase: only hashes are stored, nothing is tallied
+        client.commit_vote(&alice, &id, &client.commitment_hash(&yes, &3, &salt_a));
+        client.commit_vote(&bob, &id, &client.commitment_hash(&no, &2, &salt_b));
+        client.commit_vote(&carol, &id, &client.commitment_hash(&yes, &1, &salt_c));
+        assert_eq!(client.try_get_results(&id), Err(Ok(VoteError::ResultsHidden)));
+        assert_eq!(client.try_get_total_votes(&id), Err(Ok(VoteError::ResultsHidden)));
         
-        // ═══════════════════════════════════════════════════════════════════
-        //                     RECORD THE VOTE
-        // ═══════════════════════════════════════════════════════════════════
-        let record = VoterRecord {
-            voter: voter.clone(),
-            choice: choice.clone(),
-            votes: vote_count,
-            timestamp: env.ledger().timestamp(),
-        };
+        // Reveals are only accepted once voting has ended
+        assert_eq!(
+            client.try_reveal_vote(&alice, &id, &yes, &1, &salt_a),
+            Err(Ok(VoteError::NotRevealPhase))
+        );
         
-        // Save voter record
-        env.storage().persistent().set(&voter, &record);
+        set_time(&env, END);
+        assert_eq!(
+            client.try_commit_vote(&alice, &id, &client.commitment_hash(&no, &3, &salt_a)),
+            Err(Ok(VoteError::PollClosed))
+        );
         
-        // ═══════════════════════════════════════════════════════════════════
-        //                     SET TTL FOR VOTER RECORD
-        // ═══════════════════════════════════════════════════════════════════
-        // extend_ttl(threshold, extend_to)
-        // If TTL < threshold, extend to extend_to
-        env.storage().persistent().extend_ttl(
-            &voter, 
-            VOTER_RECORD_TTL_THRESHOLD,  // If less than 1 month left
-            VOTER_RECORD_TTL             // Extend to 1 year
+        // The revealed choice, weight and salt must hash to the commitment
+        assert_eq!(
+            client.try_reveal_vote(&alice, &id, &no, &3, &salt_a),
+            Err(Ok(VoteError::CommitmentMismatch))
+        );
+        assert_eq!(
+            client.try_reveal_vote(&alice, &id, &yes, &3, &salt_b),
+            Err(Ok(VoteError::CommitmentMismatch))
         );
+        client.reveal_vote(&alice, &id, &yes, &3, &salt_a);
         
-        // ═══════════════════════════════════════════════════════════════════
-        //                     UPDATE TOTALS
-        // ═══════════════════════════════════════════════════════════════════
-        if choice == yes {
-            let current_yes: u64 = env.storage().instance().get(&TOTAL_YES).unwrap_or(0);
-            env.storage().instance().set(&TOTAL_YES, &(current_yes + vote_count));
-        } else {
-            let current_no: u64 = env.storage().instance().get(&TOTAL_NO).unwrap_or(0);
-            env.storage().instance().set(&TOTAL_NO, &(current_no + vote_count));
-        }
+        // Seeing alice's reveal does not let carol pick a heavier weight
+        assert_eq!(
+            client.try_reveal_vote(&carol, &id, &yes, &5, &salt_c),
+            Err(Ok(VoteError::CommitmentMismatch))
+        );
+        client.reveal_vote(&bob, &id, &no, &2, &salt_b);
+        assert_eq!(
+            client.try_reveal_vote(&bob, &id, &no, &2, &salt_b),
+            Err(Ok(VoteError::AlreadyVoted))
+        );
         
-        let total: u64 = env.storage().instance().get(&TOTAL_VOTES).unwrap_or(0);
-        env.storage().instance().set(&TOTAL_VOTES, &(total + vote_count));
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_reveal_vote(&stranger, &id, &yes, &1, &salt_a),
+            Err(Ok(VoteError::CommitmentNotFound))
+        );
+        assert_eq!(client.get_unrevealed(&id), vec![&env, carol.clone()]);
         
-        // ═══════════════════════════════════════════════════════════════════
-        //                     EXTEND INSTANCE TTL
-        // ═══════════════════════════════════════════════════════════════════
-        // Every time someone votes, extend the contract's life
-        env.storage().instance().extend_ttl(
-            INSTANCE_TTL_THRESHOLD,  // If less than 1 month left
-            INSTANCE_TTL             // Extend to 1 year
+        // Results, ballots (and closing) wait for the reveal phase to end
+        assert_eq!(client.try_get_winner(&id), Err(Ok(VoteError::ResultsHidden)));
+        assert_eq!(client.try_get_voter(&id, &alice), Err(Ok(VoteError::ResultsHidden)));
+        assert_eq!(client.try_list_voters(&id, &0, &10), Err(Ok(VoteError::ResultsHidden)));
+        assert_eq!(client.try_close_poll(&creator, &id), Err(Ok(VoteError::ResultsHidden)));
+        
+        set_time(&env, END + 500);
+        assert_eq!(
+            client.try_reveal_vote(&carol, &id, &yes, &1, &salt_c),
+            Err(Ok(VoteError::NotRevealPhase))
         );
+        assert_eq!(client.get_results(&id), map![&env, (yes.clone(), 3), (no.clone(), 2)]);
+        assert_eq!(client.get_winner(&id), PollOutcome::Passed(yes));
+        assert_eq!(client.get_voter(&id, &alice).unwrap().votes, 3);
+        assert_eq!(client.list_voters(&id, &0, &10).len(), 2);
+        client.close_poll(&creator, &id);
     }
     
-    // ─────────────────────────────────────────────────────────────────────────
-    //                     VIEW FUNCTIONS
-    // ─────────────────────────────────────────────────────────────────────────
-    
-    pub fn get_yes_votes(env: Env) -> u64 {
-        env.storage().instance().get(&TOTAL_YES).unwrap_or(0)
+    #[test]
+    fn test_secret_ballot_rules() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, open_id) = setup(&env);
+        let creator = Address::generate(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let salt = BytesN::from_array(&env, &[7; 32]);
+        
+        // The reveal phase must come after the voting window
+        let mut cfg = config(&env, "Secret");
+        cfg.reveal_end_time = Some(END);
+        assert_eq!(
+            client.try_create_proposal(&creator, &cfg),
+            Err(Ok(VoteError::InvalidWindow))
+        );
+        cfg.reveal_end_time = Some(END + 1);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        // Plain ballots and delegation would leak the choice
+        assert_eq!(client.try_vote(&alice, &id, &yes, &1), Err(Ok(VoteError::SecretBallot)));
+        assert_eq!(client.try_delegate(&alice, &id, &bob), Err(Ok(VoteError::SecretBallot)));
+        assert_eq!(
+            client.try_change_vote(&alice, &id, &yes),
+            Err(Ok(VoteError::SecretBallot))
+        );
+        assert_eq!(client.try_retract_vote(&alice, &id), Err(Ok(VoteError::SecretBallot)));
+        
+        // Commitments only make sense in a secret poll
+        let hash = client.commitment_hash(&yes, &1, &salt);
+        assert_eq!(
+            client.try_commit_vote(&alice, &open_id, &hash),
+            Err(Ok(VoteError::NotSecretBallot))
+        );
+        assert_eq!(
+            client.try_reveal_vote(&alice, &open_id, &yes, &1, &salt),
+            Err(Ok(VoteError::NotSecretBallot))
+        );
+        
+        // Committing twice replaces the hash without listing the voter twice
+        client.commit_vote(&alice, &id, &hash);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(&env, "vote_committed"), id, alice.clone()).into_val(&env),
+                    hash.into_val(&env),
+                ),
+            ]
+        );
+        let other = client.commitment_hash(&String::from_str(&env, "no"), &1, &salt);
+        client.commit_vote(&alice, &id, &other);
+        assert_eq!(client.get_unrevealed(&id), vec![&env, alice.clone()]);
+        
+        set_time(&env, END);
+        assert_eq!(
+            client.try_reveal_vote(&alice, &id, &yes, &1, &salt),
+            Err(Ok(VoteError::CommitmentMismatch))
+        );
     }
     
-    pub fn get_no_votes(env: Env) -> u64 {
-        env.storage().instance().get(&TOTAL_NO).unwrap_or(0)
+    #[test]
+    fn test_registered_eligibility() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, open_id) = setup(&env);
+        let admin = client.get_admin();
+        let creator = Address::generate(&env);
+        
+        let mut cfg = config(&env, "Internal budget");
+        cfg.eligibility = Eligibility::Registered;
+        let id = client.create_proposal(&creator, &cfg);
+        
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        let mallory = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        client.register_voter(&alice);
+        assert_eq!(env.auths()[0].0, admin);
+        client.register_voters(&vec![&env, bob.clone(), carol.clone()]);
+        assert!(client.is_registered(&bob));
+        assert!(!client.is_registered(&mallory));
+        
+        // Unregistered addresses can neither vote nor delegate
+        assert_eq!(client.try_vote(&mallory, &id, &yes, &1), Err(Ok(VoteError::NotEligible)));
+        assert_eq!(
+            client.try_delegate(&mallory, &id, &alice),
+            Err(Ok(VoteError::NotEligible))
+        );
+        
+        // Open polls are unaffected by the registry
+        client.vote(&mallory, &open_id, &yes, &1);
+        
+        // A revoked delegator's weight is dropped when the delegate votes
+        client.delegate(&carol, &id, &alice);
+        client.revoke_voter(&carol);
+        client.vote(&alice, &id, &yes, &2);
+        assert!(!client.has_voted(&id, &carol));
+        assert_eq!(client.get_total_votes(&id), 2);
+        
+        client.revoke_voter(&bob);
+        assert_eq!(client.try_vote(&bob, &id, &yes, &1), Err(Ok(VoteError::NotEligible)));
     }
     
-    pub fn get_total_votes(env: Env) -> u64 {
-        env.storage().instance().get(&TOTAL_VOTES).unwrap_or(0)
+    #[test]
+    #[should_panic]
+    fn test_register_requires_admin_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup(&env);
+        
+        // Drop the mocks: the admin has not signed
+        env.set_auths(&[]);
+        client.register_voter(&Address::generate(&env));
     }
     
-    pub fn get_voter(env: Env, voter: Address) -> Option<VoterRecord> {
-        env.storage().persistent().get(&voter)
+    #[test]
+    fn test_quadratic_split_with_proof() {
+        let env = Env::default();
+        env.mock_all_auths();
+        
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        let leaves = [merkle_leaf(&env, &alice), merkle_leaf(&env, &bob)];
+        let mut cfg = config(&env, "Allowlisted grant");
+        cfg.mode = VotingMode::Quadratic(100);
+        cfg.eligibility = Eligibility::MerkleRoot(merkle_parent(&env, &leaves[0], &leaves[1]), 2);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        // Splits in an allowlisted poll need the voter's proof
+        let split = map![&env, (yes.clone(), 6), (no.clone(), 8)];
+        assert_eq!(client.try_vote_quadratic(&alice, &id, &split), Err(Ok(VoteError::InvalidProof)));
+        client.vote_quadratic_with_proof(&alice, &id, &split, &vec![&env, leaves[1].clone()]);
+        assert_eq!(client.get_voter(&id, &alice).unwrap().credits_spent, 100);
+        assert_eq!(client.get_results(&id), split);
+        
+        assert_eq!(
+            client.try_vote_quadratic_with_proof(&outsider, &id, &split, &vec![&env, leaves[1].clone()]),
+            Err(Ok(VoteError::InvalidProof))
+        );
     }
     
-    pub fn has_voted(env: Env, voter: Address) -> bool {
-        env.storage().persistent().has(&voter)
+    fn merkle_leaf(env: &Env, voter: &Address) -> BytesN<32> {
+        env.crypto().sha256(&voter.clone().to_xdr(env)).into()
     }
-    
-    pub fn get_results(env: Env) -> (u64, u64, u64) {
-        let yes = Self::get_yes_votes(env.clone());
-        let no = Self::get_no_votes(env.clone());
-        let total = Self::get_total_votes(env);
-        (yes, no, total)
+
+    fn merkle_parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        let mut pair = Bytes::from(low.clone());
+        pair.append(&Bytes::from(high.clone()));
+        env.crypto().sha256(&pair).into()
     }
-    
-    pub fn get_winner(env: Env) -> String {
-        let yes = Self::get_yes_votes(env.clone());
-        let no = Self::get_no_votes(env.clone());
+
+    #[test]
+    fn test_merkle_eligibility() {
+        let env = Env::default();
+        env.mock_all_auths();
         
-        if yes > no {
-            String::from_str(&env, "yes")
-        } else if no > yes {
-            String::from_str(&env, "no")
-        } else {
-            String::from_str(&env, "tie")
-        }
-    }
-    
-    // ─────────────────────────────────────────────────────────────────────────
-    //                     TTL MANAGEMENT FUNCTIONS
-    // ─────────────────────────────────────────────────────────────────────────
-    
-    /// Extend the contract instance TTL (anyone can call this)
-    /// Useful to keep the contract alive if no one is voting
-    pub fn extend_contract_ttl(env: Env) {
-        env.storage().instance().extend_ttl(
-            INSTANCE_TTL_THRESHOLD,
-            INSTANCE_TTL
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        let voters: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+        let outsider = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        
+        //        root
+        //       /    \
+        //     ab      cd
+        //    /  \    /  \
+        //   a    b  c    d
+        let leaves: [BytesN<32>; 4] = core::array::from_fn(|i| merkle_leaf(&env, &voters[i]));
+        let ab = merkle_parent(&env, &leaves[0], &leaves[1]);
+        let cd = merkle_parent(&env, &leaves[2], &leaves[3]);
+        let root = merkle_parent(&env, &ab, &cd);
+        
+        let mut cfg = config(&env, "Network upgrade");
+        cfg.eligibility = Eligibility::MerkleRoot(root, 4);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        // Sibling leaf, then the other subtree
+        client.vote_with_proof(&voters[0], &id, &yes, &2, &vec![&env, leaves[1].clone(), cd.clone()]);
+        client.vote_with_proof(&voters[3], &id, &yes, &1, &vec![&env, leaves[2].clone(), ab.clone()]);
+        assert_eq!(client.get_total_votes(&id), 3);
+        
+        // A valid proof does not allow a second ballot
+        assert_eq!(
+            client.try_vote_with_proof(&voters[0], &id, &yes, &2, &vec![&env, leaves[1].clone(), cd.clone()]),
+            Err(Ok(VoteError::AlreadyVoted))
         );
-    }
-    
-    /// Extend a specific voter's record TTL
-    /// The voter can call this to keep their vote record alive
-    pub fn extend_voter_ttl(env: Env, voter: Address) {
-        // Only the voter can extend their own record
-        voter.require_auth();
         
-        assert!(
-            env.storage().persistent().has(&voter),
-            "Voter record not found"
+        // Someone else's proof, no proof, or an address outside the tree all fail
+        assert_eq!(
+            client.try_vote_with_proof(&voters[2], &id, &yes, &1, &vec![&env, leaves[1].clone(), cd.clone()]),
+            Err(Ok(VoteError::InvalidProof))
+        );
+        assert_eq!(client.try_vote(&voters[2], &id, &yes, &1), Err(Ok(VoteError::InvalidProof)));
+        assert_eq!(
+            client.try_vote_with_proof(&outsider, &id, &yes, &1, &vec![&env, leaves[3].clone(), ab.clone()]),
+            Err(Ok(VoteError::InvalidProof))
         );
         
-        env.storage().persistent().extend_ttl(
-            &voter,
-            VOTER_RECORD_TTL_THRESHOLD,
-            VOTER_RECORD_TTL
+        // Delegators have no proof to show
+        assert_eq!(
+            client.try_delegate(&voters[1], &id, &voters[2]),
+            Err(Ok(VoteError::InvalidProof))
         );
+        client.vote_with_proof(&voters[2], &id, &yes, &1, &vec![&env, leaves[3].clone(), ab]);
     }
-}
-
-// ═══════════════════════════════════════════════════════════════════════════════
-//                                  TESTS
-// ═══════════════════════════════════════════════════════════════════════════════
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
-
+    
     #[test]
-    fn test_vote_yes() {
+    fn test_quorum_and_threshold() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
-        
-        let alice = Address::generate(&env);
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        let voters: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
         let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        // A percent quorum needs a non-empty electorate, and 1-100 percent
+        let mut cfg = config(&env, "Quorum");
+        cfg.quorum = Quorum::Percent(50);
+        assert_eq!(
+            client.try_create_proposal(&creator, &cfg),
+            Err(Ok(VoteError::InvalidQuorum))
+        );
+        cfg.eligibility = Eligibility::Registered;
+        assert_eq!(
+            client.try_create_proposal(&creator, &cfg),
+            Err(Ok(VoteError::InvalidQuorum))
+        );
+        cfg.quorum = Quorum::Percent(101);
+        client.register_voter(&voters[0]);
+        assert_eq!(
+            client.try_create_proposal(&creator, &cfg),
+            Err(Ok(VoteError::InvalidQuorum))
+        );
+        
+        // 50% of a 4-voter registry, two-thirds of the votes to pass
+        client.register_voters(&vec![
+            &env,
+            voters[0].clone(),
+            voters[1].clone(),
+            voters[2].clone(),
+            voters[3].clone(),
+        ]);
+        client.register_voter(&voters[0]);
+        assert_eq!(client.get_registered_count(), 4);
+        cfg.quorum = Quorum::Percent(50);
+        cfg.threshold = Threshold::TwoThirds;
+        let percent = client.create_proposal(&creator, &cfg);
+        
+        // Two absolute ballots, simple majority
+        let mut cfg = config(&env, "Ballots");
+        cfg.quorum = Quorum::Ballots(2);
+        cfg.threshold = Threshold::SimpleMajority;
+        let absolute = client.create_proposal(&creator, &cfg);
+        
+        client.vote(&voters[0], &percent, &yes, &4);
+        client.vote(&voters[0], &absolute, &yes, &3);
+        
+        set_time(&env, END);
+        assert_eq!(client.get_ballot_count(&percent), 1);
+        assert_eq!(client.get_winner(&percent), PollOutcome::QuorumNotMet);
+        assert_eq!(client.get_winner(&absolute), PollOutcome::QuorumNotMet);
         
-        client.vote(&alice, &yes, &3);
+        // The electorate was fixed at creation: changing the registry after
+        // the poll ended cannot turn the outcome around
+        assert_eq!(client.get_proposal(&percent).electorate, 4);
+        client.revoke_voter(&voters[2]);
+        client.revoke_voter(&voters[3]);
+        assert_eq!(client.get_winner(&percent), PollOutcome::QuorumNotMet);
+        client.register_voter(&Address::generate(&env));
+        assert_eq!(client.get_winner(&percent), PollOutcome::QuorumNotMet);
         
-        assert_eq!(client.get_yes_votes(), 3);
-        assert_eq!(client.get_no_votes(), 0);
-        assert_eq!(client.get_total_votes(), 3);
-        assert_eq!(client.has_voted(&alice), true);
+        // Quorum met, but 3 against 3 is a tie
+        set_time(&env, START);
+        client.vote(&voters[1], &absolute, &no, &3);
+        set_time(&env, END);
+        assert_eq!(client.get_winner(&absolute), PollOutcome::Tie(vec![&env, yes.clone(), no.clone()]));
+        
+        // Unanimity fails on a single dissenting vote
+        let mut cfg = config(&env, "Unanimous");
+        cfg.start_time = END;
+        cfg.end_time = END + 10;
+        cfg.threshold = Threshold::Unanimity;
+        let unanimous = client.create_proposal(&creator, &cfg);
+        client.vote(&voters[0], &unanimous, &yes, &5);
+        client.vote(&voters[1], &unanimous, &no, &1);
+        set_time(&env, END + 10);
+        assert_eq!(client.get_winner(&unanimous), PollOutcome::ThresholdNotMet(yes));
     }
     
     #[test]
-    fn test_vote_no() {
+    fn test_list_voters() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
-        
-        let bob = Address::generate(&env);
+        // Quadratic, so one of the ballots can be a split
+        let (client, _) = setup(&env);
+        let mut cfg = config(&env, "Audit trail");
+        cfg.mode = VotingMode::Quadratic(100);
+        let id = client.create_proposal(&Address::generate(&env), &cfg);
+        let voters: [Address; 6] = core::array::from_fn(|_| Address::generate(&env));
+        let yes = String::from_str(&env, "yes");
         let no = String::from_str(&env, "no");
         
-        client.vote(&bob, &no, &5);
+        for (i, voter) in voters[0..5].iter().enumerate() {
+            let choice = if i % 2 == 0 { &yes } else { &no };
+            client.vote(voter, &id, choice, &(i as u64 + 1));
+        }
+        client.vote_quadratic(&voters[5], &id, &map![&env, (yes.clone(), 3), (no.clone(), 4)]);
+        
+        // Retracting leaves a gap; voting again reuses the same position
+        client.retract_vote(&voters[1], &id);
+        client.retract_vote(&voters[3], &id);
+        client.vote(&voters[3], &id, &yes, &2);
+        assert_eq!(client.get_voter_index_len(&id), 6);
+        
+        let first = client.list_voters(&id, &0, &2);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first.get(0).unwrap().voter, voters[0]);
+        let rest = client.list_voters(&id, &2, &10);
+        assert_eq!(rest.len(), 4);
+        assert_eq!(rest.get(1).unwrap().voter, voters[3]);
+        assert_eq!(rest.get(3).unwrap().votes, 7);
+        assert_eq!(client.list_voters(&id, &6, &10).len(), 0);
         
-        assert_eq!(client.get_yes_votes(), 0);
-        assert_eq!(client.get_no_votes(), 5);
-        assert_eq!(client.get_total_votes(), 5);
+        // An auditor's recount over all pages matches the stored tally;
+        // a split counts per option, not all on its `choice`
+        let mut recount: Map<String, u64> = map![&env, (yes.clone(), 0), (no.clone(), 0)];
+        for page in [first, rest] {
+            for record in page.iter() {
+                let split = if record.allocation.is_empty() {
+                    map![&env, (record.choice.clone(), record.votes)]
+                } else {
+                    record.allocation
+                };
+                for (option, votes) in split.iter() {
+                    recount.set(option.clone(), recount.get(option).unwrap() + votes);
+                }
+            }
+        }
+        assert_eq!(recount, client.get_results(&id));
+        
+        assert_eq!(
+            client.try_list_voters(&99, &0, &10),
+            Err(Ok(VoteError::ProposalNotFound))
+        );
     }
     
     #[test]
-    fn test_multiple_voters() {
+    fn test_sponsored_voter_ttl() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
-        
+        let (client, id) = setup(&env);
         let alice = Address::generate(&env);
         let bob = Address::generate(&env);
-        let charlie = Address::generate(&env);
+        let stranger = Address::generate(&env);
         let yes = String::from_str(&env, "yes");
-        let no = String::from_str(&env, "no");
+        client.vote(&alice, &id, &yes, &1);
+        client.vote(&bob, &id, &yes, &2);
+        
+        // The tracked TTL matches what the host reports for the record
+        let host_ttl = |voter: &Address| {
+            env.as_contract(&client.address, || {
+                env.storage().persistent().get_ttl(&DataKey::Voter(id, voter.clone()))
+            })
+        };
+        let everyone = vec![&env, alice.clone(), bob.clone(), stranger.clone()];
+        let ttls = client.get_voter_ttls(&id, &everyone);
+        assert_eq!(ttls.len(), 2);
+        assert_eq!(ttls.get(0).unwrap().ttl, host_ttl(&alice));
+        assert!(!ttls.get(0).unwrap().near_archival);
+        
+        // Half a month before expiry both records are close to archival
+        env.ledger().with_mut(|li| li.sequence_number += VOTER_RECORD_TTL - VOTER_RECORD_TTL_THRESHOLD / 2);
+        let ttls = client.get_voter_ttls(&id, &everyone);
+        assert!(ttls.iter().all(|t| t.near_archival));
+        assert_eq!(ttls.get(1).unwrap().ttl, host_ttl(&bob));
         
-        client.vote(&alice, &yes, &3);
-        client.vote(&bob, &no, &5);
-        client.vote(&charlie, &yes, &2);
+        // No signatures needed to sponsor the rent; unknown voters are skipped
+        env.set_auths(&[]);
+        assert_eq!(client.extend_voters_ttl(&id, &everyone), 2);
+        let ttls = client.get_voter_ttls(&id, &everyone);
+        assert_eq!(ttls.get(0).unwrap().ttl, VOTER_RECORD_TTL);
+        assert_eq!(ttls.get(0).unwrap().ttl, host_ttl(&alice));
+        assert!(!ttls.get(1).unwrap().near_archival);
         
-        assert_eq!(client.get_yes_votes(), 5);
-        assert_eq!(client.get_no_votes(), 5);
-        assert_eq!(client.get_total_votes(), 10);
-        assert_eq!(client.get_winner(), String::from_str(&env, "tie"));
+        // The voter index is kept alive with the records, so list_voters still works
+        env.as_contract(&client.address, || {
+            for key in [
+                DataKey::VoterAt(id, 1),
+                DataKey::VoterSlot(id, bob.clone()),
+                DataKey::VoterIndexLen(id),
+            ] {
+                assert_eq!(env.storage().persistent().get_ttl(&key), VOTER_RECORD_TTL);
+            }
+        });
+        assert_eq!(client.list_voters(&id, &0, &10).len(), 2);
+        
+        // An index entry that did archive is skipped rather than trapping
+        env.as_contract(&client.address, || {
+            env.storage().persistent().remove(&DataKey::VoterAt(id, 0));
+        });
+        assert_eq!(client.list_voters(&id, &0, &10).get(0).unwrap().voter, bob);
     }
     
     #[test]
-    fn test_get_voter_record() {
+    fn test_finalize() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
-        
+        let (client, id) = setup(&env);
         let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
         let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
         
-        client.vote(&alice, &yes, &4);
+        client.vote(&alice, &id, &yes, &4);
+        client.vote(&bob, &id, &no, &1);
+        assert_eq!(client.try_finalize(&id), Err(Ok(VoteError::PollNotClosed)));
+        assert_eq!(client.try_get_poll_result(&id), Err(Ok(VoteError::NotFinalized)));
         
-        let record = client.get_voter(&alice);
-        assert!(record.is_some());
+        set_time(&env, END + 5);
+        env.ledger().with_mut(|li| li.sequence_number = 4_242);
+        let result = client.finalize(&id);
+        assert_eq!(
+            result,
+            PollResult {
+                proposal_id: id,
+                tally: map![&env, (yes.clone(), 4), (no.clone(), 1)],
+                total_votes: 5,
+                ballot_count: 2,
+                outcome: PollOutcome::Passed(yes.clone()),
+                ledger_sequence: 4_242,
+                timestamp: END + 5,
+            }
+        );
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(&env, "poll_finalized"), id).into_val(&env),
+                    result.into_val(&env),
+                ),
+            ]
+        );
         
-        let record = record.unwrap();
-        assert_eq!(record.voter, alice);
-        assert_eq!(record.choice, yes);
-        assert_eq!(record.votes, 4);
+        // The snapshot is final: no second finalize, no more ballots
+        assert_eq!(client.get_poll_result(&id), result);
+        assert!(client.get_proposal(&id).closed);
+        assert_eq!(client.try_finalize(&id), Err(Ok(VoteError::AlreadyFinalized)));
+        assert_eq!(
+            client.try_vote(&Address::generate(&env), &id, &yes, &1),
+            Err(Ok(VoteError::PollClosed))
+        );
+        assert_eq!(client.try_retract_vote(&alice, &id), Err(Ok(VoteError::PollClosed)));
     }
     
     #[test]
-    #[should_panic(expected = "You have already voted")]
-    fn test_cannot_vote_twice() {
+    fn test_finalized_poll_ttl() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
-        
-        let alice = Address::generate(&env);
+        let (client, id) = setup(&env);
         let yes = String::from_str(&env, "yes");
-        let no = String::from_str(&env, "no");
+        client.vote(&Address::generate(&env), &id, &yes, &2);
+        set_time(&env, END + 5);
+        client.finalize(&id);
+        
+        let ttls = || {
+            env.as_contract(&client.address, || {
+                [
+                    DataKey::Proposal(id),
+                    DataKey::Tally(id),
+                    DataKey::TotalVotes(id),
+                    DataKey::BallotCount(id),
+                    DataKey::Result(id),
+                ]
+                .map(|key| env.storage().persistent().get_ttl(&key))
+            })
+        };
+        assert_eq!(ttls(), [PROPOSAL_TTL; 5]);
+        
+        // Eleven months on, one unsigned call keeps the whole outcome alive
+        env.ledger().with_mut(|li| li.sequence_number += PROPOSAL_TTL - PROPOSAL_TTL_THRESHOLD + 1);
+        assert!(ttls().iter().all(|ttl| *ttl < PROPOSAL_TTL_THRESHOLD));
+        env.set_auths(&[]);
+        client.extend_poll_ttl(&id);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(&env, "ttl_extended"),).into_val(&env),
+                    TtlExtension { proposal_id: Some(id), voter: None, extend_to: PROPOSAL_TTL }.into_val(&env),
+                ),
+            ]
+        );
+        assert_eq!(ttls(), [PROPOSAL_TTL; 5]);
+        assert_eq!(client.get_poll_result(&id).total_votes, 2);
         
-        client.vote(&alice, &yes, &3);
-        client.vote(&alice, &no, &2);
+        assert_eq!(client.try_extend_poll_ttl(&99), Err(Ok(VoteError::ProposalNotFound)));
     }
     
     #[test]
-    #[should_panic(expected = "Maximum 5 votes")]
-    fn test_max_votes() {
+    fn test_ranked_choice_runoff() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
+        let (client, standard_id) = setup(&env);
+        let creator = Address::generate(&env);
+        let red = String::from_str(&env, "red");
+        let green = String::from_str(&env, "green");
+        let blue = String::from_str(&env, "blue");
+        
+        let mut cfg = config(&env, "Board seat");
+        cfg.options = vec![&env, red.clone(), green.clone(), blue.clone()];
+        cfg.mode = VotingMode::RankedChoice;
+        let id = client.create_proposal(&creator, &cfg);
+        
+        let voters: [Address; 9] = core::array::from_fn(|_| Address::generate(&env));
+        for voter in &voters[0..4] {
+            client.vote_ranked(voter, &id, &vec![&env, red.clone()]);
+        }
+        for voter in &voters[4..7] {
+            client.vote_ranked(voter, &id, &vec![&env, green.clone(), blue.clone()]);
+        }
+        for voter in &voters[7..9] {
+            client.vote_ranked(voter, &id, &vec![&env, blue.clone(), green.clone(), red.clone()]);
+        }
+        
+        // The tally shows first preferences; the record keeps the full ranking
+        assert_eq!(client.get_option_votes(&id, &red), 4);
+        let record = client.get_voter(&id, &voters[8]).unwrap();
+        assert_eq!(record.choice, blue);
+        assert_eq!(record.ranking.len(), 3);
+        
+        // Red leads round one, but blue's transfers put green over half
+        let report = client.get_runoff(&id);
+        assert_eq!(
+            report.rounds,
+            vec![
+                &env,
+                RunoffRound {
+                    counts: map![&env, (red.clone(), 4), (green.clone(), 3), (blue.clone(), 2)],
+                    eliminated: vec![&env, blue.clone()],
+                },
+                RunoffRound {
+                    counts: map![&env, (red.clone(), 4), (green.clone(), 5)],
+                    eliminated: vec![&env],
+                },
+            ]
+        );
+        assert_eq!(report.winner, Some(green.clone()));
+        
+        set_time(&env, END);
+        assert_eq!(client.get_winner(&id), PollOutcome::Passed(green.clone()));
+        
+        // The runoff follows retractions: without blue's transfers red has a majority
+        set_time(&env, START);
+        client.retract_vote(&voters[7], &id);
+        client.retract_vote(&voters[8], &id);
+        assert_eq!(client.get_runoff(&id).winner, Some(red.clone()));
+        
+        // Ranked polls only take ranked ballots, and vice versa
+        let late = Address::generate(&env);
+        assert_eq!(client.try_vote(&late, &id, &red, &1), Err(Ok(VoteError::RankedBallot)));
+        assert_eq!(
+            client.try_vote_ranked(&late, &standard_id, &vec![&env, String::from_str(&env, "yes")]),
+            Err(Ok(VoteError::NotRankedChoice))
+        );
+        assert_eq!(
+            client.try_get_runoff(&standard_id),
+            Err(Ok(VoteError::NotRankedChoice))
+        );
+        assert_eq!(
+            client.try_change_vote(&voters[0], &id, &green),
+            Err(Ok(VoteError::RankedBallot))
+        );
+        
+        // Rankings must be non-empty, known and without repeats
+        assert_eq!(
+            client.try_vote_ranked(&late, &id, &vec![&env]),
+            Err(Ok(VoteError::InvalidRanking))
+        );
+        assert_eq!(
+            client.try_vote_ranked(&late, &id, &vec![&env, red.clone(), red.clone()]),
+            Err(Ok(VoteError::InvalidRanking))
+        );
+        assert_eq!(
+            client.try_vote_ranked(&late, &id, &vec![&env, String::from_str(&env, "pink")]),
+            Err(Ok(VoteError::InvalidChoice))
+        );
+        
+        // Ranked ballots cannot be secret
+        cfg.reveal_end_time = Some(END + 100);
+        assert_eq!(
+            client.try_create_proposal(&creator, &cfg),
+            Err(Ok(VoteError::RankedBallot))
+        );
+        
+        // Six candidates would allow too many distinct rankings
+        cfg.reveal_end_time = None;
+        cfg.options = vec![&env];
+        for name in ["a", "b", "c", "d", "e", "f"] {
+            cfg.options.push_back(String::from_str(&env, name));
+        }
+        assert_eq!(
+            client.try_create_proposal(&creator, &cfg),
+            Err(Ok(VoteError::InvalidOptions))
+        );
+    }
+    
+    #[test]
+    fn test_ranked_choice_with_proof() {
+        let env = Env::default();
+        env.mock_all_auths();
         
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
         let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
         let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        
+        let leaves = [merkle_leaf(&env, &alice), merkle_leaf(&env, &bob)];
+        let mut cfg = config(&env, "Allowlisted board seat");
+        cfg.mode = VotingMode::RankedChoice;
+        cfg.eligibility = Eligibility::MerkleRoot(merkle_parent(&env, &leaves[0], &leaves[1]), 2);
+        let id = client.create_proposal(&creator, &cfg);
+        
+        // Ranked ballots in an allowlisted poll need the voter's proof
+        let ranking = vec![&env, no.clone(), yes.clone()];
+        assert_eq!(client.try_vote_ranked(&alice, &id, &ranking), Err(Ok(VoteError::InvalidProof)));
+        client.vote_ranked_with_proof(&alice, &id, &ranking, &vec![&env, leaves[1].clone()]);
+        client.vote_ranked_with_proof(&bob, &id, &vec![&env, no.clone()], &vec![&env, leaves[0].clone()]);
+        assert_eq!(client.get_voter(&id, &alice).unwrap().ranking, ranking);
         
-        client.vote(&alice, &yes, &10);
+        assert_eq!(
+            client.try_vote_ranked_with_proof(&Address::generate(&env), &id, &ranking, &vec![&env, leaves[1].clone()]),
+            Err(Ok(VoteError::InvalidProof))
+        );
+        set_time(&env, END + 5);
+        assert_eq!(client.get_winner(&id), PollOutcome::Passed(no));
     }
     
     #[test]
-    #[should_panic(expected = "Must cast at least 1")]
-    fn test_min_votes() {
+    fn test_ranked_choice_tie() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
+        let (client, _) = setup(&env);
+        let creator = Address::generate(&env);
+        let red = String::from_str(&env, "red");
+        let green = String::from_str(&env, "green");
+        let blue = String::from_str(&env, "blue");
         
-        let alice = Address::generate(&env);
-        let yes = String::from_str(&env, "yes");
+        let mut cfg = config(&env, "Deadlock");
+        cfg.options = vec![&env, red.clone(), green.clone(), blue.clone()];
+        cfg.mode = VotingMode::RankedChoice;
+        let id = client.create_proposal(&creator, &cfg);
+        
+        // One first preference each, and no second choices to transfer
+        client.vote_ranked(&Address::generate(&env), &id, &vec![&env, red.clone()]);
+        client.vote_ranked(&Address::generate(&env), &id, &vec![&env, green.clone()]);
+        client.vote_ranked(&Address::generate(&env), &id, &vec![&env, blue.clone()]);
+        
+        // Round one eliminates all three (tied last); nobody wins
+        let report = client.get_runoff(&id);
+        assert_eq!(report.rounds.len(), 1);
+        assert_eq!(report.winner, None);
+        
+        set_time(&env, END);
+        assert_eq!(
+            client.get_winner(&id),
+            PollOutcome::Tie(vec![&env, blue, green, red])
+        );
+    }
+    
+    /// Token-weighted poll where every voter can be minted any u64 weight
+    fn setup_weighted(env: &Env) -> (VoteContractClient<'_>, u32, token::StellarAssetClient<'_>) {
+        let (client, _) = setup(env);
+        let token_id = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
         
-        client.vote(&alice, &yes, &0);
+        let mut cfg = config(env, "Weighted");
+        cfg.mode = VotingMode::TokenWeighted(token_id.clone());
+        let id = client.create_proposal(&Address::generate(env), &cfg);
+        
+        (client, id, token::StellarAssetClient::new(env, &token_id))
     }
     
     #[test]
-    #[should_panic(expected = "Choice must be")]
-    fn test_invalid_choice() {
+    fn test_tally_overflow() {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
+        let (client, id, token) = setup_weighted(&env);
+        let whale = Address::generate(&env);
+        let orca = Address::generate(&env);
+        let yes = String::from_str(&env, "yes");
+        let no = String::from_str(&env, "no");
+        token.mint(&whale, &(u64::MAX as i128));
+        token.mint(&orca, &1);
         
-        let alice = Address::generate(&env);
-        let invalid = String::from_str(&env, "maybe");
+        client.vote(&whale, &id, &yes, &u64::MAX);
         
-        client.vote(&alice, &invalid, &3);
+        // A different option still pushes the poll total past u64::MAX
+        assert_eq!(client.try_vote(&orca, &id, &no, &1), Err(Ok(VoteError::Overflow)));
+        assert!(!client.has_voted(&id, &orca));
+        assert_eq!(client.get_total_votes(&id), u64::MAX);
+        
+        // Moving weight between options cannot overflow
+        client.change_vote(&whale, &id, &no);
+        assert_eq!(client.get_option_votes(&id, &no), u64::MAX);
+    }
+    
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        
+        /// Random weights (many near u64::MAX) from fresh voters must match a
+        /// u128 reference tally; ballots that would overflow are rejected
+        /// with Overflow and leave no trace.
+        #[test]
+        fn prop_tally_matches_reference(
+            ballots in prop::collection::vec(
+                (
+                    0usize..2,
+                    prop_oneof![1u64..=5, any::<u64>().prop_map(|w| w.max(1)), (u64::MAX - 8)..=u64::MAX],
+                ),
+                1..10,
+            )
+        ) {
+            // Inputs are random, so a snapshot per case would change on every run
+            let env = Env::new_with_config(EnvTestConfig { capture_snapshot_at_drop: false });
+            env.mock_all_auths();
+            
+            let (client, id, token) = setup_weighted(&env);
+            let options = ["yes", "no"].map(|o| String::from_str(&env, o));
+            
+            let mut reference = [0u128; 2];
+            for (choice, weight) in ballots {
+                let voter = Address::generate(&env);
+                token.mint(&voter, &(weight as i128));
+                
+                let result = client.try_vote(&voter, &id, &options[choice], &weight);
+                let total: u128 = reference.iter().sum::<u128>() + weight as u128;
+                if total > u64::MAX as u128 {
+                    prop_assert_eq!(result, Err(Ok(VoteError::Overflow)));
+                    prop_assert!(!client.has_voted(&id, &voter));
+                } else {
+                    prop_assert_eq!(result, Ok(Ok(())));
+                    reference[choice] += weight as u128;
+                }
+            }
+            
+            for (option, expected) in options.iter().zip(reference) {
+                prop_assert_eq!(client.get_option_votes(&id, option) as u128, expected);
+            }
+            prop_assert_eq!(client.get_total_votes(&id) as u128, reference.iter().sum::<u128>());
+        }
     }
     
     #[test]
@@ -362,8 +4086,7 @@ mod test {
         let env = Env::default();
         env.mock_all_auths();
         
-        let contract_id = env.register(VoteContract, ());
-        let client = VoteContractClient::new(&env, &contract_id);
+        let (client, id) = setup(&env);
         
         // Extend contract TTL (should not panic)
         client.extend_contract_ttl();
@@ -371,10 +4094,17 @@ mod test {
         // Vote first, then extend voter TTL
         let alice = Address::generate(&env);
         let yes = String::from_str(&env, "yes");
-        client.vote(&alice, &yes, &3);
+        client.vote(&alice, &id, &yes, &3);
         
         // Extend Alice's voter record TTL
-        client.extend_voter_ttl(&alice);
+        client.extend_voter_ttl(&id, &alice);
+        
+        // Bob never voted, so there is no record to extend
+        let bob = Address::generate(&env);
+        assert_eq!(
+            client.try_extend_voter_ttl(&id, &bob),
+            Err(Ok(VoteError::VoterNotFound))
+        );
     }
 }
 
@@ -422,7 +4152,9 @@ mod test {
 ║  ─────────────────────────────────────────────────────────────────────────── ║
 ║  • extend_contract_ttl: Anyone can keep contract alive                       ║
 ║  • extend_voter_ttl: Voter can keep their vote record alive                  ║
+║  • extend_voters_ttl: Anyone can pay to keep a batch of records alive        ║
+║  • get_voter_ttls: Shows which records are close to being archived           ║
 ║  • These are "maintenance" functions to prevent data expiry                  ║
 ║                                                                              ║
 ╚══════════════════════════════════════════════════════════════════════════════╝
-*/
\ No newline at end of file
+*/
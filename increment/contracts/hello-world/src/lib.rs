@@ -1,44 +1,58 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, log, symbol_short, Env, Symbol};
+use soroban_sdk::{contract, contracterror, contractimpl, log, symbol_short, Env, Symbol};
 
+// The one and only counter value, kept in instance storage.
+// Instances deployed before `make_count_by_*`/`get_count` were storage-backed
+// already hold their `increment` count here, so they carry on from that value.
 const COUNTER: Symbol = symbol_short!("COUNTER");
-static mut x: u32 = 0;
-    
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum IncrementError {
+    Overflow = 1, // the count would pass u32::MAX
+}
+
 #[contract]
 pub struct IncrementContract;
 
 #[contractimpl]
 impl IncrementContract {
     /// Increment increments an internal counter, and returns the value.
-    pub fn increment(env: Env) -> u32 {
-        let mut count: u32 = env.storage().instance().get(&COUNTER).unwrap_or(0);
-        log!(&env, "count: {}", count);
-
-        count += 1;
-        env.storage().instance().set(&COUNTER, &count);
-        env.storage().instance().extend_ttl(50, 100);
-
-        count
+    pub fn increment(env: Env) -> Result<u32, IncrementError> {
+        Self::add(&env, 1)
     }
 
-    pub fn make_count_by_1(env: Env) -> u32 {
-            x += 1;
-            return x;
+    pub fn make_count_by_1(env: Env) -> Result<u32, IncrementError> {
+        Self::add(&env, 1)
     }
 
     pub fn reset(env: Env) {
         env.storage().instance().remove(&COUNTER);
-            x = 0;
     }
 
     pub fn get_count(env: Env) -> u32 {
-            return x;
+        env.storage().instance().get(&COUNTER).unwrap_or(0)
     }
 
-    pub fn make_count_by_value(env: Env, val: u32) -> u32 {
-            x += val;
-            return x;
+    pub fn make_count_by_value(env: Env, val: u32) -> Result<u32, IncrementError> {
+        Self::add(&env, val)
+    }
+}
+
+impl IncrementContract {
+    /// Adds `val` to the stored count and returns the new value.
+    /// Fails without touching the count if it would overflow.
+    fn add(env: &Env, val: u32) -> Result<u32, IncrementError> {
+        let count = Self::get_count(env.clone());
+        log!(env, "count: {}", count);
+
+        let count = count.checked_add(val).ok_or(IncrementError::Overflow)?;
+        env.storage().instance().set(&COUNTER, &count);
+        env.storage().instance().extend_ttl(50, 100);
+
+        Ok(count)
     }
 }
 
-mod test;
\ No newline at end of file
+mod test;
@@ -1,5 +1,5 @@
 #![cfg(test)] // This is a compiler directive that tells Rust: "Only compile this file when running tests." It won't be included in the final build.
-use crate::{IncrementContract, IncrementContractClient};
+use crate::{IncrementContract, IncrementContractClient, IncrementError, COUNTER};
 use soroban_sdk::Env;
 
 #[test]
@@ -28,9 +28,9 @@ fn test_make_count_by_value() {
     let env = Env::default();
     let contract_id = env.register(IncrementContract, ());
     let client = IncrementContractClient::new(&env, &contract_id);
-    assert_eq!(client.make_count_by_value(5), 5);
-    assert_eq!(client.make_count_by_value(10), 15);
-    assert_eq!(client.make_count_by_value(20), 35);
+    assert_eq!(client.make_count_by_value(&5), 5);
+    assert_eq!(client.make_count_by_value(&10), 15);
+    assert_eq!(client.make_count_by_value(&20), 35);
 }
 
 #[test]
@@ -52,6 +52,72 @@ fn test_get_count() {
     assert_eq!(client.get_count(), 0);
     client.make_count_by_1();
     assert_eq!(client.get_count(), 1);
-    client.make_count_by_value(4);
+    client.make_count_by_value(&4);
     assert_eq!(client.get_count(), 5);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_all_entrypoints_share_one_value() {
+    let env = Env::default();
+    let contract_id = env.register(IncrementContract, ());
+    let client = IncrementContractClient::new(&env, &contract_id);
+    assert_eq!(client.increment(), 1);
+    assert_eq!(client.make_count_by_1(), 2);
+    assert_eq!(client.make_count_by_value(&3), 5);
+    assert_eq!(client.increment(), 6);
+    assert_eq!(client.get_count(), 6);
+    client.reset();
+    assert_eq!(client.get_count(), 0);
+}
+
+#[test]
+fn test_count_survives_separate_invocations() {
+    let env = Env::default();
+    let contract_id = env.register(IncrementContract, ());
+
+    // Every client call is its own invocation; nothing is kept in memory between them
+    IncrementContractClient::new(&env, &contract_id).make_count_by_value(&7);
+    let client = IncrementContractClient::new(&env, &contract_id);
+    assert_eq!(client.get_count(), 7);
+
+    // The value lives in the contract's storage
+    let stored: u32 = env.as_contract(&contract_id, || env.storage().instance().get(&COUNTER).unwrap());
+    assert_eq!(stored, 7);
+
+    // A second instance has its own count
+    let other = IncrementContractClient::new(&env, &env.register(IncrementContract, ()));
+    assert_eq!(other.get_count(), 0);
+    assert_eq!(client.make_count_by_1(), 8);
+}
+
+#[test]
+fn test_existing_counter_value_is_picked_up() {
+    let env = Env::default();
+    let contract_id = env.register(IncrementContract, ());
+
+    // An instance that already counted to 41 with the old `increment`
+    env.as_contract(&contract_id, || env.storage().instance().set(&COUNTER, &41_u32));
+
+    let client = IncrementContractClient::new(&env, &contract_id);
+    assert_eq!(client.get_count(), 41);
+    assert_eq!(client.make_count_by_1(), 42);
+    assert_eq!(client.increment(), 43);
+}
+
+#[test]
+fn test_overflow_is_rejected() {
+    let env = Env::default();
+    let contract_id = env.register(IncrementContract, ());
+    env.as_contract(&contract_id, || env.storage().instance().set(&COUNTER, &(u32::MAX - 5)));
+
+    let client = IncrementContractClient::new(&env, &contract_id);
+    assert_eq!(client.make_count_by_value(&4), u32::MAX - 1);
+    assert_eq!(client.increment(), u32::MAX);
+
+    // Every entrypoint fails at the top and leaves the count alone
+    assert_eq!(client.try_increment(), Err(Ok(IncrementError::Overflow)));
+    assert_eq!(client.try_make_count_by_1(), Err(Ok(IncrementError::Overflow)));
+    assert_eq!(client.try_make_count_by_value(&1), Err(Ok(IncrementError::Overflow)));
+    assert_eq!(client.get_count(), u32::MAX);
+    assert_eq!(client.make_count_by_value(&0), u32::MAX);
+}